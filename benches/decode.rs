@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use morsify::MorseCode;
+
+const MESSAGE: &str = "- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --.";
+
+fn decode(c: &mut Criterion) {
+    let morse_code = MorseCode::default();
+
+    c.bench_function("decode short message", |b| {
+        b.iter(|| morse_code.decode(black_box("... --- ...")));
+    });
+    c.bench_function("decode pangram", |b| {
+        b.iter(|| morse_code.decode(black_box(MESSAGE)));
+    });
+    c.bench_function("try_decode pangram", |b| {
+        b.iter(|| morse_code.try_decode(black_box(MESSAGE)));
+    });
+}
+
+fn new(c: &mut Criterion) {
+    c.bench_function("new", |b| b.iter(MorseCode::default));
+}
+
+criterion_group!(benches, decode, new);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+//! A builder for [`Options`] that validates the configuration before handing it out.
+
+use alloc::borrow::Cow;
+
+use crate::{InvalidCharPolicy, MorseCharacterSet, Options, OptionsError, UnknownSequencePolicy};
+
+/// Builds [`Options`], checking that the resulting configuration can be decoded unambiguously.
+///
+/// Created by [`Options::builder`], which starts from the default options.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{Options, OptionsError, SymbolKind};
+///
+/// let options = Options::builder().dot("dit").dash("dah").build()?;
+/// assert_eq!(options.dot, "dit");
+///
+/// let error = Options::builder().space(" ").build().unwrap_err();
+/// assert_eq!(
+///     error,
+///     OptionsError::SameSymbol {
+///         first: SymbolKind::Space,
+///         second: SymbolKind::Separator,
+///         symbol: " ".into(),
+///     }
+/// );
+/// # Ok::<(), OptionsError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct OptionsBuilder {
+    options: Options,
+}
+
+impl OptionsBuilder {
+    /// Creates a builder starting from the default options.
+    pub const fn new() -> Self {
+        Self {
+            options: Options::new(),
+        }
+    }
+
+    /// Sets the symbol used to represent a dash.
+    pub fn dash<S: Into<Cow<'static, str>>>(mut self, dash: S) -> Self {
+        self.options.dash = dash.into();
+        self
+    }
+
+    /// Sets the symbol used to represent a dot.
+    pub fn dot<S: Into<Cow<'static, str>>>(mut self, dot: S) -> Self {
+        self.options.dot = dot.into();
+        self
+    }
+
+    /// Sets the symbol used to represent a space between words.
+    pub fn space<S: Into<Cow<'static, str>>>(mut self, space: S) -> Self {
+        self.options.space = space.into();
+        self
+    }
+
+    /// Sets the symbol used to separate Morse code characters.
+    pub fn separator<S: Into<Cow<'static, str>>>(mut self, separator: S) -> Self {
+        self.options.separator = separator.into();
+        self
+    }
+
+    /// Sets the character set that takes priority when several share a code.
+    pub const fn priority(mut self, priority: MorseCharacterSet) -> Self {
+        self.options.priority = priority;
+        self
+    }
+
+    /// Sets the character sets looked up after the priority set, in order.
+    pub fn fallback<S: Into<Cow<'static, [MorseCharacterSet]>>>(mut self, fallback: S) -> Self {
+        self.options.fallback = fallback.into();
+        self
+    }
+
+    /// Restricts the built-in character sets looked up to the given ones.
+    pub fn allowed<S: Into<Cow<'static, [MorseCharacterSet]>>>(mut self, allowed: S) -> Self {
+        self.options.allowed = Some(allowed.into());
+        self
+    }
+
+    /// Sets whether to switch between international Morse code and Wabun code with prosigns.
+    pub const fn wabun_switching(mut self, wabun_switching: bool) -> Self {
+        self.options.wabun_switching = wabun_switching;
+        self
+    }
+
+    /// Sets the policy for characters without a Morse code representation.
+    pub fn invalid_char_policy(mut self, policy: InvalidCharPolicy) -> Self {
+        self.options.invalid_char_policy = policy;
+        self
+    }
+
+    /// Sets whether characters without a Morse code representation are transliterated.
+    pub const fn transliterate(mut self, transliterate: bool) -> Self {
+        self.options.transliterate = transliterate;
+        self
+    }
+
+    /// Sets the policy for sequences that match no character.
+    pub fn unknown_sequence_policy(mut self, policy: UnknownSequencePolicy) -> Self {
+        self.options.unknown_sequence_policy = policy;
+        self
+    }
+
+    /// Returns the options, after checking them with [`Options::validate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OptionsError`] describing the first symbol collision or ambiguity found.
+    pub fn build(self) -> Result<Options, OptionsError> {
+        self.options.validate()?;
+        Ok(self.options)
+    }
+}
@@ -0,0 +1,225 @@
+//! User-defined character sets and modifications of the built-in ones.
+
+use alloc::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+use crate::{tables, tables::Table, MappingError, MorseCharacterSet, MorseSequence};
+
+/// A set of characters and their Morse codes that can be edited at run time.
+///
+/// A character set either starts empty, to hold a private alphabet or house abbreviations, or as a
+/// copy of a built-in [`MorseCharacterSet`], to add, override or remove some of its mappings. It is
+/// then handed to a [`MorseCode`](crate::MorseCode) with
+/// [`MorseCode::add_character_set`](crate::MorseCode::add_character_set) or
+/// [`MorseCode::replace_character_set`](crate::MorseCode::replace_character_set).
+///
+/// Characters are matched after conversion to upper case, as [`MorseCode::encode`](crate::MorseCode::encode)
+/// matches them, so letters are stored in upper case. Within a set, every new code must be unused:
+/// when several characters of a built-in set already share a code, they keep doing so, and the one
+/// with the lowest code point is decoded.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{CharacterSet, MorseCharacterSet, MorseCode, MorseSequence};
+///
+/// let mut punctuation = CharacterSet::from(MorseCharacterSet::Punctuation);
+/// punctuation.insert('%', MorseSequence::from_code("-----.-----"))?;
+/// punctuation.remove('¡');
+///
+/// let mut morse_code = MorseCode::default();
+/// morse_code.replace_character_set(MorseCharacterSet::Punctuation, punctuation);
+///
+/// assert_eq!(morse_code.encode("%"), "-----.-----");
+/// assert_eq!(morse_code.decode("--...-"), "--...-");
+/// # Ok::<(), morsify::MappingError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct CharacterSet {
+    /// The code of every character of the set.
+    codes: BTreeMap<char, MorseSequence>,
+    /// Every code of the set with its characters, sorted by code and then by character.
+    characters: BTreeSet<(MorseSequence, char)>,
+}
+
+impl CharacterSet {
+    /// Creates an empty character set.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            codes: BTreeMap::new(),
+            characters: BTreeSet::new(),
+        }
+    }
+
+    /// Maps `character` to `code`, overriding its previous code if it had one.
+    ///
+    /// # Returns
+    ///
+    /// The previous code of the character, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MappingError`] if `code` is empty or already used by another character of the
+    /// set, in which case the set is left unchanged.
+    pub fn insert(
+        &mut self,
+        character: char,
+        code: MorseSequence,
+    ) -> Result<Option<MorseSequence>, MappingError> {
+        let character = normalize(character);
+        if code.is_empty() {
+            return Err(MappingError::EmptyCode { character });
+        }
+        if let Some(existing) = self.characters_of(code).find(|&c| c != character) {
+            return Err(MappingError::CodeInUse {
+                character,
+                code,
+                existing,
+            });
+        }
+
+        let previous = self.code(character);
+        self.insert_shared(character, code);
+        Ok(previous)
+    }
+
+    /// Creates a set from mappings, inserting them in order with [`CharacterSet::insert`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`MappingError`] for the first mapping whose code is empty or already used by
+    /// another character.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{CharacterSet, MappingError, MorseSequence};
+    ///
+    /// let code = MorseSequence::from_code("-.-.-");
+    /// let set = CharacterSet::try_from_iter([('X', code), ('Y', code)]);
+    ///
+    /// assert_eq!(
+    ///     set,
+    ///     Err(MappingError::CodeInUse { character: 'Y', code, existing: 'X' })
+    /// );
+    /// ```
+    pub fn try_from_iter<I: IntoIterator<Item = (char, MorseSequence)>>(
+        iter: I,
+    ) -> Result<Self, MappingError> {
+        let mut set = Self::new();
+        for (character, code) in iter {
+            set.insert(character, code)?;
+        }
+        Ok(set)
+    }
+
+    /// Removes `character` from the set, returning its code if it was present.
+    pub fn remove(&mut self, character: char) -> Option<MorseSequence> {
+        let character = normalize(character);
+        let code = self.codes.remove(&character)?;
+        self.characters.remove(&(code, character));
+        Some(code)
+    }
+
+    /// Returns the code of `character`, if the set contains it.
+    #[must_use]
+    pub fn code(&self, character: char) -> Option<MorseSequence> {
+        self.codes.get(&normalize(character)).copied()
+    }
+
+    /// Returns the character `code` decodes to, if the set contains it.
+    ///
+    /// When several characters share the code, the one with the lowest code point is returned.
+    #[must_use]
+    pub fn character(&self, code: MorseSequence) -> Option<char> {
+        self.characters_of(code).next()
+    }
+
+    /// Returns the number of characters in the set.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// Returns `true` if the set contains no character.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Returns an iterator over the characters of the set and their codes, sorted by character.
+    pub fn iter(&self) -> impl Iterator<Item = (char, MorseSequence)> + '_ {
+        self.codes.iter().map(|(&c, &code)| (c, code))
+    }
+
+    /// Maps `character` to `code` even if other characters of the set already use the code, as
+    /// in the built-in tables.
+    pub(crate) fn insert_shared(&mut self, character: char, code: MorseSequence) {
+        let character = normalize(character);
+        if let Some(previous) = self.codes.insert(character, code) {
+            self.characters.remove(&(previous, character));
+        }
+        self.characters.insert((code, character));
+    }
+
+    /// Returns the characters whose code is exactly `code`, in ascending order.
+    fn characters_of(&self, code: MorseSequence) -> impl Iterator<Item = char> + '_ {
+        self.characters
+            .range((code, '\0')..)
+            .take_while(move |&&(other, _)| other == code)
+            .map(|&(_, c)| c)
+    }
+}
+
+impl From<MorseCharacterSet> for CharacterSet {
+    /// Copies the built-in table of the given set, or creates an empty set for
+    /// [`MorseCharacterSet::Undefined`].
+    fn from(set: MorseCharacterSet) -> Self {
+        tables::table(set)
+            .map(|table| {
+                let mut set = Self::new();
+                for &(character, code) in table.entries() {
+                    set.insert_shared(character, code);
+                }
+                set
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// A character set as seen by lookups: either a built-in table or a [`CharacterSet`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum SetRef<'a> {
+    /// An unmodified built-in table.
+    Table(&'static Table),
+    /// A custom or modified set.
+    Custom(&'a CharacterSet),
+}
+
+impl SetRef<'_> {
+    /// Returns the code of `c`, if the set contains it.
+    pub(crate) fn code(self, c: char) -> Option<MorseSequence> {
+        match self {
+            Self::Table(table) => table.code(c),
+            Self::Custom(set) => set.code(c),
+        }
+    }
+
+    /// Returns the character `sequence` decodes to, if the set contains it.
+    pub(crate) fn character(self, sequence: MorseSequence) -> Option<char> {
+        match self {
+            Self::Table(table) => table.character(sequence),
+            Self::Custom(set) => set.character(sequence),
+        }
+    }
+}
+
+/// Converts `c` to upper case, the form characters are stored in, if it has a single-character
+/// upper case.
+fn normalize(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(upper), None) => upper,
+        _ => c,
+    }
+}
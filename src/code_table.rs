@@ -0,0 +1,233 @@
+//! A plain-text format for loading and saving Morse code tables.
+
+use alloc::{collections::btree_map::BTreeMap, string::ToString, vec::Vec};
+use core::{fmt, str::FromStr};
+
+use crate::{
+    tables, CharacterSet, MorseCharacterSet, MorseSequence, ParseTableError, TableErrorKind,
+};
+
+/// The complete set of mappings of a [`MorseCode`](crate::MorseCode), as read from or written to
+/// a plain-text table.
+///
+/// # Format
+///
+/// A table is a list of lines, each holding a set header, a mapping, a comment or nothing:
+///
+/// - A set header, such as `[Latin]` or `[LatinExtended]`, names a [`MorseCharacterSet`] and
+///   starts its mappings. The header `[Custom]` starts a new custom character set instead.
+/// - A mapping is a character followed by its code written with `.` and `-`, such as `A .-`.
+///   Characters that would otherwise be read as part of the format, namely `#`, `[` and whitespace,
+///   are written as their code point, such as `U+0023`; any other character may be written this way
+///   too.
+/// - Everything from a `#` to the end of the line is a comment.
+///
+/// As in the built-in tables, several characters of a set may share a code, in which case the one
+/// with the lowest code point is decoded.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{CodeTable, MorseCode, Options};
+///
+/// let table: CodeTable = "
+///     [Numbers]  # digits only, with cut numbers
+///     0 -
+///     1 .-
+///     9 -.
+/// "
+/// .parse()?;
+///
+/// let morse_code = MorseCode::from_code_table(Options::default(), table);
+/// assert_eq!(morse_code.encode("190"), ".- -. -");
+/// assert_eq!(morse_code.encode("A"), "A");
+/// # Ok::<(), morsify::ParseTableError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct CodeTable {
+    /// The mappings of the built-in character sets. Sets missing from the map have no mappings.
+    pub sets: BTreeMap<MorseCharacterSet, CharacterSet>,
+    /// The custom character sets, in lookup order.
+    pub custom: Vec<CharacterSet>,
+}
+
+impl FromStr for CodeTable {
+    type Err = ParseTableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut table = Self::default();
+        let mut current = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let error = |kind| ParseTableError {
+                line: index + 1,
+                kind,
+            };
+            let line = line.split('#').next().unwrap_or_default().trim();
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                if name == "Custom" {
+                    table.custom.push(CharacterSet::new());
+                    current = Some(None);
+                    continue;
+                }
+                let set = tables::ALL
+                    .into_iter()
+                    .find(|set| set.to_string() == name)
+                    .ok_or_else(|| error(TableErrorKind::UnknownSet(name.to_string())))?;
+                if table.sets.insert(set, CharacterSet::new()).is_some() {
+                    return Err(error(TableErrorKind::DuplicateSet(set)));
+                }
+                current = Some(Some(set));
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let Some(character) = fields.next() else {
+                continue;
+            };
+            let set = match current {
+                None => return Err(error(TableErrorKind::MappingOutsideSet)),
+                Some(Some(set)) => table.sets.get_mut(&set),
+                Some(None) => table.custom.last_mut(),
+            }
+            .expect("the current set has been created by its header");
+
+            let character = parse_character(character)
+                .ok_or_else(|| error(TableErrorKind::InvalidCharacter(character.to_string())))?;
+            let code = fields
+                .next()
+                .ok_or_else(|| error(TableErrorKind::MissingCode))?
+                .parse::<MorseSequence>()
+                .map_err(|e| error(TableErrorKind::InvalidCode(e)))?;
+            if let Some(field) = fields.next() {
+                return Err(error(TableErrorKind::UnexpectedField(field.to_string())));
+            }
+            if set.code(character).is_some() {
+                return Err(error(TableErrorKind::DuplicateCharacter(character)));
+            }
+            set.insert_shared(character, code);
+        }
+
+        Ok(table)
+    }
+}
+
+impl fmt::Display for CodeTable {
+    /// Writes the table in the format it is parsed from, one set after another.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sets = self
+            .sets
+            .iter()
+            .map(|(set, characters)| (Some(set), characters));
+        let custom = self.custom.iter().map(|characters| (None, characters));
+
+        for (i, (set, characters)) in sets.chain(custom).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match set {
+                Some(set) => writeln!(f, "[{set}]")?,
+                None => writeln!(f, "[Custom]")?,
+            }
+            for (character, code) in characters.iter() {
+                if character == '#' || character == '[' || character.is_whitespace() {
+                    writeln!(f, "U+{:04X} {code}", u32::from(character))?;
+                } else {
+                    writeln!(f, "{character} {code}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parses the character field of a mapping, either a single character or a code point such as
+/// `U+0023`.
+fn parse_character(field: &str) -> Option<char> {
+    let mut chars = field.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(c);
+    }
+    let hex = field.strip_prefix("U+")?;
+    if !(4..=6).contains(&hex.len()) {
+        return None;
+    }
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MorseCode;
+
+    #[test]
+    fn round_trips_tables() {
+        let text = MorseCode::default().code_table().to_string();
+        assert!(text.starts_with("[Latin]\nA .-\nB -...\n"));
+        assert!(text.contains("\n\n[Numbers]\n0 -----\n"));
+
+        let table = text.parse::<CodeTable>().unwrap();
+        assert_eq!(table, MorseCode::default().code_table());
+        let morse_code = MorseCode::from_code_table(Default::default(), table);
+        assert_eq!(morse_code.encode("ÀÁ"), ".--.- .--.-");
+        assert_eq!(morse_code.decode(".--.- -.-- ---."), "ÀYÒ");
+
+        let mut custom = CharacterSet::new();
+        custom
+            .insert('#', MorseSequence::from_code("-.-.-"))
+            .unwrap();
+        let table = CodeTable {
+            custom: [custom].into(),
+            ..Default::default()
+        };
+        assert_eq!(table.to_string(), "[Custom]\nU+0023 -.-.-\n");
+        assert_eq!(table.to_string().parse(), Ok(table));
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        let error = |text: &str| text.parse::<CodeTable>().unwrap_err();
+        assert_eq!(
+            error("A .-"),
+            ParseTableError {
+                line: 1,
+                kind: TableErrorKind::MappingOutsideSet
+            }
+        );
+        assert_eq!(
+            error("[Latin]\n# comment\n\nA .-\nB -x.."),
+            ParseTableError {
+                line: 5,
+                kind: TableErrorKind::InvalidCode(crate::ParseSequenceError::InvalidSymbol {
+                    symbol: 'x',
+                    index: 1
+                })
+            }
+        );
+        assert_eq!(error("[Custom]\nAB .-").line, 2);
+        assert_eq!(error("[Custom]\nA").kind, TableErrorKind::MissingCode);
+        assert_eq!(
+            error("[Custom]\nA .- .-").kind,
+            TableErrorKind::UnexpectedField(".-".into())
+        );
+        assert_eq!(
+            error("[Custom]\nA .-\na -").kind,
+            TableErrorKind::DuplicateCharacter('a')
+        );
+        assert_eq!(
+            error("[Latin]\n[Latin]").kind,
+            TableErrorKind::DuplicateSet(MorseCharacterSet::Latin)
+        );
+        assert_eq!(
+            error("[Klingon]").kind,
+            TableErrorKind::UnknownSet("Klingon".into())
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! Letter-frequency models for guessing the alphabet of a Morse code message.
+
+use alloc::string::String;
+
+use crate::{charset::SetRef, MorseCharacterSet, MorseSequence};
+
+/// One way of decoding a message, as returned by
+/// [`MorseCode::decode_auto`](crate::MorseCode::decode_auto).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interpretation {
+    /// The character set the letters of the message were decoded with.
+    pub set: MorseCharacterSet,
+    /// The decoded text.
+    pub text: String,
+    /// How plausible the decoding is: the average base-2 logarithm of the probability of each
+    /// letter and unrecognized token under the letter frequencies of the set. It is never positive,
+    /// and the closer to zero, the more plausible.
+    pub score: f64,
+}
+
+/// The letter frequencies of the language most commonly written with a character set.
+#[derive(Debug)]
+pub(crate) struct Model {
+    /// The character set the model applies to.
+    pub(crate) set: MorseCharacterSet,
+    /// Letters and their frequency, in hundredths of a percent of the letters of a typical text.
+    frequencies: &'static [(char, u32)],
+}
+
+/// The number of fractional bits of the costs returned by [`Model`].
+const FRACTION_BITS: u32 = 8;
+
+impl Model {
+    /// Returns the cost of decoding `c`, the negative base-2 logarithm of its probability in fixed
+    /// point. Letters missing from the model are given the frequency of the rarest possible one.
+    pub(crate) fn cost(&self, c: char) -> u32 {
+        let frequency = self
+            .frequencies
+            .iter()
+            .find(|&&(letter, _)| letter == c)
+            .map_or(1, |&(_, frequency)| frequency);
+        log2(self.total()) - log2(frequency)
+    }
+
+    /// Returns the cost of a token that decodes to nothing in the set, as if it were sixteen times
+    /// less likely than the rarest possible letter: four bits more than its cost.
+    pub(crate) fn unrecognized_cost(&self) -> u32 {
+        log2(self.total()) + (4 << FRACTION_BITS)
+    }
+
+    /// Returns the letter of the model that `sequence` stands for in `set`, the most frequent one
+    /// if several letters of the set share the code, or `None` if the model has no such letter.
+    pub(crate) fn letter(&self, set: SetRef<'_>, sequence: MorseSequence) -> Option<char> {
+        self.frequencies
+            .iter()
+            .filter(|&&(letter, _)| set.code(letter) == Some(sequence))
+            .max_by_key(|&&(_, frequency)| frequency)
+            .map(|&(letter, _)| letter)
+    }
+
+    /// Returns the average of `cost` over `count` letters as a score of an [`Interpretation`].
+    pub(crate) fn score(cost: u64, count: u64) -> f64 {
+        if count == 0 {
+            return 0.0;
+        }
+        -(cost as f64) / (count as f64) / f64::from(1 << FRACTION_BITS)
+    }
+
+    fn total(&self) -> u32 {
+        self.frequencies
+            .iter()
+            .map(|&(_, frequency)| frequency)
+            .sum()
+    }
+}
+
+/// Returns the base-2 logarithm of `x` in fixed point with [`FRACTION_BITS`] fractional bits.
+const fn log2(x: u32) -> u32 {
+    let integer = x.ilog2();
+    // The mantissa, in [1, 2) with 31 fractional bits.
+    let mut mantissa = (x as u64) << (31 - integer);
+    let mut log = integer << FRACTION_BITS;
+    let mut bit = 1 << (FRACTION_BITS - 1);
+    while bit > 0 {
+        mantissa = (mantissa * mantissa) >> 31;
+        if mantissa >= 2 << 31 {
+            mantissa >>= 1;
+            log += bit;
+        }
+        bit >>= 1;
+    }
+    log
+}
+
+/// The models of every character set whose letters can be told apart by frequency, in the order
+/// interpretations that score alike are ranked.
+pub(crate) static MODELS: [Model; 9] = [
+    Model {
+        set: MorseCharacterSet::Latin,
+        // English.
+        frequencies: &[
+            ('A', 817),
+            ('B', 149),
+            ('C', 278),
+            ('D', 425),
+            ('E', 1270),
+            ('F', 223),
+            ('G', 202),
+            ('H', 609),
+            ('I', 697),
+            ('J', 15),
+            ('K', 77),
+            ('L', 403),
+            ('M', 241),
+            ('N', 675),
+            ('O', 751),
+            ('P', 193),
+            ('Q', 10),
+            ('R', 599),
+            ('S', 633),
+            ('T', 906),
+            ('U', 276),
+            ('V', 98),
+            ('W', 236),
+            ('X', 15),
+            ('Y', 197),
+            ('Z', 7),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Cyrillic,
+        // Russian.
+        frequencies: &[
+            ('А', 801),
+            ('Б', 159),
+            ('В', 454),
+            ('Г', 170),
+            ('Д', 298),
+            ('Е', 849),
+            ('Ж', 94),
+            ('З', 165),
+            ('И', 735),
+            ('Й', 121),
+            ('К', 349),
+            ('Л', 440),
+            ('М', 321),
+            ('Н', 670),
+            ('О', 1097),
+            ('П', 281),
+            ('Р', 473),
+            ('С', 547),
+            ('Т', 626),
+            ('У', 262),
+            ('Ф', 26),
+            ('Х', 97),
+            ('Ц', 48),
+            ('Ч', 144),
+            ('Ш', 73),
+            ('Щ', 36),
+            ('Ъ', 4),
+            ('Ы', 190),
+            ('Ь', 174),
+            ('Э', 32),
+            ('Ю', 64),
+            ('Я', 201),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Greek,
+        // Modern Greek.
+        frequencies: &[
+            ('Α', 1200),
+            ('Β', 70),
+            ('Γ', 170),
+            ('Δ', 180),
+            ('Ε', 800),
+            ('Ζ', 40),
+            ('Η', 480),
+            ('Θ', 130),
+            ('Ι', 790),
+            ('Κ', 400),
+            ('Λ', 280),
+            ('Μ', 350),
+            ('Ν', 660),
+            ('Ξ', 40),
+            ('Ο', 980),
+            ('Π', 420),
+            ('Ρ', 430),
+            ('Σ', 710),
+            ('Τ', 800),
+            ('Υ', 450),
+            ('Φ', 80),
+            ('Χ', 120),
+            ('Ψ', 10),
+            ('Ω', 210),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Hebrew,
+        // Modern Hebrew.
+        frequencies: &[
+            ('א', 630),
+            ('ב', 470),
+            ('ג', 130),
+            ('ד', 260),
+            ('ה', 890),
+            ('ו', 1040),
+            ('ז', 100),
+            ('ח', 240),
+            ('ט', 120),
+            ('י', 1110),
+            ('כ', 280),
+            ('ל', 740),
+            ('מ', 600),
+            ('נ', 450),
+            ('ס', 150),
+            ('ע', 390),
+            ('פ', 170),
+            ('צ', 120),
+            ('ק', 190),
+            ('ר', 560),
+            ('ש', 440),
+            ('ת', 530),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Arabic,
+        // Modern Standard Arabic.
+        frequencies: &[
+            ('ا', 1250),
+            ('ب', 400),
+            ('ت', 450),
+            ('ث', 50),
+            ('ج', 150),
+            ('ح', 200),
+            ('خ', 90),
+            ('د', 330),
+            ('ذ', 60),
+            ('ر', 480),
+            ('ز', 70),
+            ('س', 250),
+            ('ش', 120),
+            ('ص', 100),
+            ('ض', 80),
+            ('ط', 90),
+            ('ظ', 30),
+            ('ع', 350),
+            ('غ', 40),
+            ('ف', 300),
+            ('ق', 280),
+            ('ك', 250),
+            ('ل', 1100),
+            ('م', 650),
+            ('ن', 580),
+            ('ه', 450),
+            ('و', 600),
+            ('ي', 700),
+            ('ﺀ', 50),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Persian,
+        // Persian.
+        frequencies: &[
+            ('ا', 1100),
+            ('ب', 400),
+            ('پ', 80),
+            ('ت', 400),
+            ('ث', 10),
+            ('ج', 100),
+            ('چ', 50),
+            ('ح', 80),
+            ('خ', 150),
+            ('د', 600),
+            ('ذ', 20),
+            ('ر', 650),
+            ('ز', 150),
+            ('ژ', 5),
+            ('س', 300),
+            ('ش', 200),
+            ('ص', 50),
+            ('ض', 10),
+            ('ط', 30),
+            ('ظ', 10),
+            ('ع', 100),
+            ('غ', 30),
+            ('ف', 100),
+            ('ق', 100),
+            ('ک', 300),
+            ('گ', 120),
+            ('ل', 250),
+            ('م', 500),
+            ('ن', 650),
+            ('و', 550),
+            ('ه', 650),
+            ('ی', 950),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Japanese,
+        // Japanese, written in katakana as in Wabun code.
+        frequencies: &[
+            ('ア', 250),
+            ('イ', 500),
+            ('ウ', 450),
+            ('エ', 100),
+            ('オ', 200),
+            ('カ', 400),
+            ('キ', 250),
+            ('ク', 250),
+            ('ケ', 150),
+            ('コ', 250),
+            ('サ', 150),
+            ('シ', 400),
+            ('ス', 300),
+            ('セ', 100),
+            ('ソ', 100),
+            ('タ', 350),
+            ('チ', 100),
+            ('ツ', 100),
+            ('テ', 300),
+            ('ト', 350),
+            ('ナ', 250),
+            ('ニ', 300),
+            ('ヌ', 20),
+            ('ネ', 50),
+            ('ノ', 500),
+            ('ハ', 250),
+            ('ヒ', 80),
+            ('フ', 80),
+            ('ヘ', 30),
+            ('ホ', 80),
+            ('マ', 200),
+            ('ミ', 100),
+            ('ム', 30),
+            ('メ', 50),
+            ('モ', 200),
+            ('ヤ', 80),
+            ('ユ', 40),
+            ('ヨ', 150),
+            ('ラ', 200),
+            ('リ', 200),
+            ('ル', 300),
+            ('レ', 200),
+            ('ロ', 80),
+            ('ワ', 100),
+            ('ヰ', 1),
+            ('ヱ', 1),
+            ('ヲ', 150),
+            ('ン', 700),
+            ('ー', 200),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Korean,
+        // Korean, counted in jamo as in SKATS.
+        frequencies: &[
+            ('ㄱ', 800),
+            ('ㄴ', 900),
+            ('ㄷ', 500),
+            ('ㄹ', 600),
+            ('ㅁ', 400),
+            ('ㅂ', 300),
+            ('ㅅ', 500),
+            ('ㅇ', 1000),
+            ('ㅈ', 400),
+            ('ㅊ', 150),
+            ('ㅋ', 50),
+            ('ㅌ', 100),
+            ('ㅍ', 80),
+            ('ㅎ', 400),
+            ('ㅏ', 1200),
+            ('ㅑ', 50),
+            ('ㅓ', 600),
+            ('ㅕ', 300),
+            ('ㅗ', 500),
+            ('ㅛ', 50),
+            ('ㅜ', 400),
+            ('ㅠ', 50),
+            ('ㅡ', 600),
+            ('ㅣ', 900),
+        ],
+    },
+    Model {
+        set: MorseCharacterSet::Thai,
+        // Thai.
+        frequencies: &[
+            ('ก', 500),
+            ('ข', 150),
+            ('ค', 250),
+            ('ง', 450),
+            ('จ', 150),
+            ('ฉ', 20),
+            ('ช', 150),
+            ('ซ', 50),
+            ('ญ', 20),
+            ('ด', 300),
+            ('ต', 250),
+            ('ถ', 50),
+            ('ท', 300),
+            ('น', 700),
+            ('บ', 250),
+            ('ป', 200),
+            ('ผ', 50),
+            ('ฝ', 10),
+            ('พ', 150),
+            ('ฟ', 30),
+            ('ม', 400),
+            ('ย', 350),
+            ('ร', 600),
+            ('ฤ', 10),
+            ('ล', 300),
+            ('ว', 300),
+            ('ส', 250),
+            ('ห', 250),
+            ('อ', 500),
+            ('ฮ', 10),
+            ('ะ', 250),
+            ('ั', 300),
+            ('า', 900),
+            ('ำ', 100),
+            ('ิ', 300),
+            ('ี', 300),
+            ('ึ', 50),
+            ('ื', 100),
+            ('ุ', 150),
+            ('ู', 80),
+            ('เ', 450),
+            ('แ', 200),
+            ('โ', 100),
+            ('ไ', 200),
+            ('ๆ', 20),
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_fixed_point_logarithms() {
+        assert_eq!(log2(1), 0);
+        assert_eq!(log2(2), 1 << FRACTION_BITS);
+        assert_eq!(log2(1024), 10 << FRACTION_BITS);
+        // log2(3) = 1.58496...
+        assert_eq!(log2(3), 405);
+        assert!(MODELS[0].cost('E') < MODELS[0].cost('Z'));
+        assert_eq!(MODELS[0].cost('Ω'), log2(MODELS[0].total()));
+    }
+}
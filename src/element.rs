@@ -0,0 +1,37 @@
+//! The individual elements Morse code is keyed with.
+
+/// A single element of keyed Morse code, as produced by [`MorseCode::elements`](crate::MorseCode::elements).
+///
+/// In standard timing a dot lasts one unit and a dash three; the gaps between the elements of a
+/// character, between characters and between words last one, three and seven units respectively.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Element {
+    /// A short signal.
+    Dot,
+    /// A long signal.
+    Dash,
+    /// The silence between the dots and dashes of a single character.
+    IntraCharGap,
+    /// The silence between two characters of a word.
+    CharGap,
+    /// The silence between two words.
+    WordGap,
+}
+
+impl Element {
+    /// Returns the duration of the element in dot units, following standard timing.
+    #[must_use]
+    pub const fn units(self) -> u32 {
+        match self {
+            Self::Dot | Self::IntraCharGap => 1,
+            Self::Dash | Self::CharGap => 3,
+            Self::WordGap => 7,
+        }
+    }
+
+    /// Returns `true` for dots and dashes, and `false` for gaps.
+    #[must_use]
+    pub const fn is_signal(self) -> bool {
+        matches!(self, Self::Dot | Self::Dash)
+    }
+}
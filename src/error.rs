@@ -0,0 +1,321 @@
+//! Error types returned by the fallible methods of [`MorseCode`](crate::MorseCode) and its companions.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, ops::Range};
+
+use crate::{MorseCharacterSet, Script};
+
+/// A Morse code token that could not be matched against any known character.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnrecognizedToken {
+    /// The token exactly as it appeared in the input.
+    pub token: String,
+    /// The byte range of the token within the input.
+    pub range: Range<usize>,
+    /// Characters whose code differs from the token by a single dot or dash
+    /// (one element changed, added or removed), in ascending order.
+    pub candidates: Vec<char>,
+}
+
+/// The error returned by [`MorseCode::try_decode`](crate::MorseCode::try_decode).
+///
+/// Decoding does not stop at the first unknown token: the error lists every token of the input
+/// that could not be decoded, in the order they appear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DecodeError {
+    tokens: Vec<UnrecognizedToken>,
+}
+
+impl DecodeError {
+    pub(crate) const fn new(tokens: Vec<UnrecognizedToken>) -> Self {
+        Self { tokens }
+    }
+
+    /// Returns every unrecognized token of the input, in the order they appear.
+    #[must_use]
+    pub fn tokens(&self) -> &[UnrecognizedToken] {
+        &self.tokens
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unrecognized Morse code")?;
+        for (i, token) in self.tokens.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(
+                f,
+                "{sep}`{}` at {}..{}",
+                token.token, token.range.start, token.range.end
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for DecodeError {}
+
+/// A character of the input that has no Morse code representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnsupportedChar {
+    /// The character as it appeared in the input.
+    pub character: char,
+    /// The index of the character within the input, counted in `char`s.
+    pub index: usize,
+    /// The Unicode script of the character.
+    pub script: Script,
+}
+
+/// The error returned by [`MorseCode::try_encode`](crate::MorseCode::try_encode).
+///
+/// Encoding does not stop at the first unsupported character: the error lists every character of
+/// the input that could not be encoded, in the order they appear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EncodeError {
+    chars: Vec<UnsupportedChar>,
+}
+
+impl EncodeError {
+    pub(crate) const fn new(chars: Vec<UnsupportedChar>) -> Self {
+        Self { chars }
+    }
+
+    /// Returns every unsupported character of the input, in the order they appear.
+    #[must_use]
+    pub fn chars(&self) -> &[UnsupportedChar] {
+        &self.chars
+    }
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unsupported characters")?;
+        for (i, c) in self.chars.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(
+                f,
+                "{sep}{:?} ({}) at index {}",
+                c.character, c.script, c.index
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for EncodeError {}
+
+/// The error returned when parsing a [`MorseSequence`](crate::MorseSequence) from a string fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseSequenceError {
+    /// The string contains a symbol other than `.` and `-`.
+    InvalidSymbol {
+        /// The offending symbol.
+        symbol: char,
+        /// The byte index of the symbol within the string.
+        index: usize,
+    },
+    /// The string holds more elements than a sequence can store.
+    TooLong,
+}
+
+impl fmt::Display for ParseSequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSymbol { symbol, index } => {
+                write!(f, "invalid Morse code symbol {symbol:?} at index {index}")
+            }
+            Self::TooLong => write!(
+                f,
+                "Morse code sequence longer than {} elements",
+                crate::MorseSequence::MAX_LEN
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ParseSequenceError {}
+
+/// The error returned when a mapping cannot be added to a [`CharacterSet`](crate::CharacterSet).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MappingError {
+    /// The code is empty, so it could not be told apart from a gap.
+    EmptyCode {
+        /// The character being mapped.
+        character: char,
+    },
+    /// The code already belongs to another character of the set.
+    CodeInUse {
+        /// The character being mapped.
+        character: char,
+        /// The code it was to be mapped to.
+        code: crate::MorseSequence,
+        /// The character of the set that already uses the code.
+        existing: char,
+    },
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyCode { character } => {
+                write!(f, "cannot map {character:?} to an empty Morse code")
+            }
+            Self::CodeInUse {
+                character,
+                code,
+                existing,
+            } => write!(
+                f,
+                "cannot map {character:?} to `{code}`, which already encodes {existing:?}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for MappingError {}
+
+/// The error returned when parsing a [`CodeTable`](crate::CodeTable) fails.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseTableError {
+    /// The number of the offending line, starting at 1.
+    pub line: usize,
+    /// What is wrong with the line.
+    pub kind: TableErrorKind,
+}
+
+/// The reason a line of a [`CodeTable`](crate::CodeTable) could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TableErrorKind {
+    /// A set header names no known character set.
+    UnknownSet(String),
+    /// A set header names a character set that already appeared in the table.
+    DuplicateSet(MorseCharacterSet),
+    /// A mapping appears before the first set header.
+    MappingOutsideSet,
+    /// The character of a mapping is neither a single character nor a code point such as `U+0023`.
+    InvalidCharacter(String),
+    /// A mapping has no code.
+    MissingCode,
+    /// The code of a mapping is not a valid Morse code sequence.
+    InvalidCode(ParseSequenceError),
+    /// A mapping is followed by another field.
+    UnexpectedField(String),
+    /// A character is mapped twice in the same set.
+    DuplicateCharacter(char),
+}
+
+impl fmt::Display for ParseTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            TableErrorKind::UnknownSet(name) => write!(f, "unknown character set `{name}`"),
+            TableErrorKind::DuplicateSet(set) => write!(f, "character set `{set}` appears twice"),
+            TableErrorKind::MappingOutsideSet => write!(f, "mapping before the first set header"),
+            TableErrorKind::InvalidCharacter(field) => write!(f, "invalid character `{field}`"),
+            TableErrorKind::MissingCode => write!(f, "missing Morse code"),
+            TableErrorKind::InvalidCode(error) => error.fmt(f),
+            TableErrorKind::UnexpectedField(field) => write!(f, "unexpected field `{field}`"),
+            TableErrorKind::DuplicateCharacter(c) => write!(f, "{c:?} is mapped twice"),
+        }
+    }
+}
+
+impl core::error::Error for ParseTableError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match &self.kind {
+            TableErrorKind::InvalidCode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// One of the symbols of [`Options`](crate::Options).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SymbolKind {
+    /// The symbol representing a dot.
+    Dot,
+    /// The symbol representing a dash.
+    Dash,
+    /// The symbol representing a space between words.
+    Space,
+    /// The symbol separating Morse code characters.
+    Separator,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dot => "dot",
+            Self::Dash => "dash",
+            Self::Space => "space",
+            Self::Separator => "separator",
+        })
+    }
+}
+
+/// The error returned when [`Options`](crate::Options) cannot be decoded unambiguously.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum OptionsError {
+    /// A symbol is empty.
+    EmptySymbol {
+        /// The offending symbol.
+        kind: SymbolKind,
+    },
+    /// Two symbols are the same.
+    SameSymbol {
+        /// The first of the two symbols.
+        first: SymbolKind,
+        /// The second of the two symbols.
+        second: SymbolKind,
+        /// The text both symbols use.
+        symbol: String,
+    },
+    /// A symbol is a character of a built-in character set.
+    TableCharacter {
+        /// The offending symbol.
+        kind: SymbolKind,
+        /// The character it uses.
+        symbol: char,
+        /// The character set the character belongs to.
+        set: MorseCharacterSet,
+    },
+    /// The space or the separator is made of dots and dashes.
+    SpelledWithElements {
+        /// The offending symbol.
+        kind: SymbolKind,
+        /// The text of the symbol.
+        symbol: String,
+    },
+    /// A symbol starts with another one, and can be mistaken for it followed by other symbols.
+    AmbiguousSymbols {
+        /// The shorter symbol.
+        first: SymbolKind,
+        /// The longer symbol, which starts with the shorter one.
+        second: SymbolKind,
+    },
+}
+
+impl fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySymbol { kind } => write!(f, "the {kind} is empty"),
+            Self::SameSymbol {
+                first,
+                second,
+                symbol,
+            } => write!(f, "the {first} and the {second} are both {symbol:?}"),
+            Self::TableCharacter { kind, symbol, set } => {
+                write!(f, "the {kind} {symbol:?} is a character of the {set} set")
+            }
+            Self::SpelledWithElements { kind, symbol } => {
+                write!(f, "the {kind} {symbol:?} is made of dots and dashes")
+            }
+            Self::AmbiguousSymbols { first, second } => write!(
+                f,
+                "the {second} can be mistaken for the {first} followed by other symbols"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for OptionsError {}
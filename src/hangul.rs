@@ -0,0 +1,291 @@
+//! Decomposition of Hangul syllables into the jamo SKATS has codes for, and their recomposition.
+//!
+//! SKATS only covers the fourteen basic consonants and ten basic vowels. A syllable is sent as its
+//! lead consonant, vowel and optional tail consonant, with double consonants, compound vowels and
+//! compound tails spelled out as the basic jamo they are made of.
+
+use core::fmt;
+
+/// The first precomposed Hangul syllable, `가`.
+const FIRST_SYLLABLE: u32 = 0xAC00;
+/// The last precomposed Hangul syllable, `힣`.
+const LAST_SYLLABLE: u32 = 0xD7A3;
+
+/// Lead consonants, in the order of the precomposed syllables.
+const LEADS: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ',
+    'ㅌ', 'ㅍ', 'ㅎ',
+];
+/// Vowels, in the order of the precomposed syllables.
+const VOWELS: [char; 21] = [
+    'ㅏ', 'ㅐ', 'ㅑ', 'ㅒ', 'ㅓ', 'ㅔ', 'ㅕ', 'ㅖ', 'ㅗ', 'ㅘ', 'ㅙ', 'ㅚ', 'ㅛ', 'ㅜ', 'ㅝ', 'ㅞ',
+    'ㅟ', 'ㅠ', 'ㅡ', 'ㅢ', 'ㅣ',
+];
+/// Tail consonants, in the order of the precomposed syllables, after the syllables without one.
+const TAILS: [char; 27] = [
+    'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ', 'ㄻ', 'ㄼ', 'ㄽ', 'ㄾ', 'ㄿ', 'ㅀ', 'ㅁ',
+    'ㅂ', 'ㅄ', 'ㅅ', 'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+/// Jamo made of two others: double consonants, compound tails and compound vowels.
+const COMPOUNDS: [(char, char, char); 27] = [
+    ('ㄱ', 'ㄱ', 'ㄲ'),
+    ('ㄷ', 'ㄷ', 'ㄸ'),
+    ('ㅂ', 'ㅂ', 'ㅃ'),
+    ('ㅅ', 'ㅅ', 'ㅆ'),
+    ('ㅈ', 'ㅈ', 'ㅉ'),
+    ('ㄱ', 'ㅅ', 'ㄳ'),
+    ('ㄴ', 'ㅈ', 'ㄵ'),
+    ('ㄴ', 'ㅎ', 'ㄶ'),
+    ('ㄹ', 'ㄱ', 'ㄺ'),
+    ('ㄹ', 'ㅁ', 'ㄻ'),
+    ('ㄹ', 'ㅂ', 'ㄼ'),
+    ('ㄹ', 'ㅅ', 'ㄽ'),
+    ('ㄹ', 'ㅌ', 'ㄾ'),
+    ('ㄹ', 'ㅍ', 'ㄿ'),
+    ('ㄹ', 'ㅎ', 'ㅀ'),
+    ('ㅂ', 'ㅅ', 'ㅄ'),
+    ('ㅏ', 'ㅣ', 'ㅐ'),
+    ('ㅑ', 'ㅣ', 'ㅒ'),
+    ('ㅓ', 'ㅣ', 'ㅔ'),
+    ('ㅕ', 'ㅣ', 'ㅖ'),
+    ('ㅗ', 'ㅏ', 'ㅘ'),
+    ('ㅘ', 'ㅣ', 'ㅙ'),
+    ('ㅗ', 'ㅣ', 'ㅚ'),
+    ('ㅜ', 'ㅓ', 'ㅝ'),
+    ('ㅝ', 'ㅣ', 'ㅞ'),
+    ('ㅜ', 'ㅣ', 'ㅟ'),
+    ('ㅡ', 'ㅣ', 'ㅢ'),
+];
+
+/// The largest number of basic jamo a syllable decomposes into, as in `뛟`.
+const MAX_JAMO: usize = 7;
+
+/// Returns the basic jamo SKATS spells `c` with, or `None` if `c` is neither a Hangul syllable nor
+/// a compound jamo.
+pub(crate) fn decompose(c: char) -> Option<impl Iterator<Item = char>> {
+    let mut jamo = ['\0'; MAX_JAMO];
+    let mut len = 0;
+    let mut push = |c| {
+        jamo[len] = c;
+        len += 1;
+    };
+
+    let code = u32::from(c);
+    if (FIRST_SYLLABLE..=LAST_SYLLABLE).contains(&code) {
+        let index = (code - FIRST_SYLLABLE) as usize;
+        let (lead, vowel, tail) = (index / 588, index / 28 % 21, index % 28);
+        split(LEADS[lead], &mut push);
+        split(VOWELS[vowel], &mut push);
+        if let Some(tail) = tail.checked_sub(1) {
+            split(TAILS[tail], &mut push);
+        }
+    } else if COMPOUNDS.iter().any(|&(_, _, compound)| compound == c) {
+        split(c, &mut push);
+    } else {
+        return None;
+    }
+    Some(jamo.into_iter().take(len))
+}
+
+/// Passes the basic jamo `c` is made of to `push`.
+fn split(c: char, push: &mut impl FnMut(char)) {
+    match COMPOUNDS.iter().find(|&&(_, _, compound)| compound == c) {
+        Some(&(first, second, _)) => {
+            split(first, push);
+            split(second, push);
+        }
+        None => push(c),
+    }
+}
+
+/// Returns the jamo made of `first` and `second`, if any.
+fn combine(first: char, second: char) -> Option<char> {
+    COMPOUNDS
+        .iter()
+        .find(|&&(a, b, _)| (a, b) == (first, second))
+        .map(|&(_, _, compound)| compound)
+}
+
+/// Returns the jamo `c` is made of, if it is a compound.
+fn parts(c: char) -> Option<(char, char)> {
+    COMPOUNDS
+        .iter()
+        .find(|&&(_, _, compound)| compound == c)
+        .map(|&(first, second, _)| (first, second))
+}
+
+/// Returns the index of `c` in `jamo`, if present.
+fn position(jamo: &[char], c: char) -> Option<usize> {
+    jamo.iter().position(|&j| j == c)
+}
+
+/// Returns `true` if `c` is a Hangul compatibility jamo, which the composer takes in.
+pub(crate) fn is_jamo(c: char) -> bool {
+    position(&LEADS, c).is_some() || position(&VOWELS, c).is_some() || position(&TAILS, c).is_some()
+}
+
+/// Recomposes decoded jamo into Hangul syllables.
+///
+/// Jamo are held back until the syllable they belong to is complete, which is only known once the
+/// next character has been decoded, or [`HangulComposer::flush`] is called. Consonants between two
+/// vowels are read greedily: they close the first syllable as far as possible, leaving the last one
+/// to open the next syllable. SKATS spells a double consonant as two single ones, so `ㄱㄱ` before a
+/// vowel is read as a tail and a lead, as in `학교`, never as the lead of `아까`, which decodes as
+/// `악가`; three of them are read as a tail and a double lead, as in `꼭꼭`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HangulComposer {
+    lead: Option<char>,
+    vowel: Option<char>,
+    tail: Option<char>,
+}
+
+impl HangulComposer {
+    /// Creates a composer holding nothing back.
+    pub(crate) const fn new() -> Self {
+        Self {
+            lead: None,
+            vowel: None,
+            tail: None,
+        }
+    }
+
+    /// Takes in the jamo `c`, writing to `out` the syllables it completes.
+    pub(crate) fn write_char<W: fmt::Write>(&mut self, c: char, out: &mut W) -> fmt::Result {
+        let is_vowel = position(&VOWELS, c).is_some();
+        match (self.lead, self.vowel, self.tail) {
+            (Some(lead), None, None) if !is_vowel => {
+                match combine(lead, c).filter(|&double| position(&LEADS, double).is_some()) {
+                    Some(double) => self.lead = Some(double),
+                    None => {
+                        self.flush(out)?;
+                        self.lead = Some(c);
+                    }
+                }
+            }
+            (Some(_), None, None) => self.vowel = Some(c),
+            (Some(_), Some(vowel), None) if is_vowel => match combine(vowel, c) {
+                Some(compound) => self.vowel = Some(compound),
+                None => {
+                    self.flush(out)?;
+                    out.write_char(c)?;
+                }
+            },
+            (Some(_), Some(_), None) if position(&TAILS, c).is_some() => self.tail = Some(c),
+            (Some(_), Some(_), Some(tail)) if is_vowel => {
+                // The last consonant opens the syllable of the vowel.
+                let (kept, lead) = match parts(tail) {
+                    Some((first, second)) => (Some(first), second),
+                    None => (None, tail),
+                };
+                self.tail = kept;
+                self.flush(out)?;
+                self.lead = Some(lead);
+                self.vowel = Some(c);
+            }
+            (Some(_), Some(_), Some(tail)) => {
+                let compound =
+                    combine(tail, c).filter(|&compound| position(&TAILS, compound).is_some());
+                let double = parts(tail).filter(|&(first, second)| first == second && second == c);
+                match (compound, double) {
+                    (Some(compound), _) => self.tail = Some(compound),
+                    // A double tail followed by the same consonant keeps one of them and passes a
+                    // double lead on.
+                    (None, Some((first, second))) => {
+                        self.tail = Some(first);
+                        self.flush(out)?;
+                        self.lead = combine(second, c);
+                    }
+                    (None, None) => {
+                        self.flush(out)?;
+                        self.lead = Some(c);
+                    }
+                }
+            }
+            _ => {
+                self.flush(out)?;
+                if is_vowel {
+                    out.write_char(c)?;
+                } else {
+                    self.lead = Some(c);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the jamo held back, as a syllable if they make one.
+    pub(crate) fn flush<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        let (lead, vowel, tail) = (self.lead.take(), self.vowel.take(), self.tail.take());
+        let syllable = lead.zip(vowel).and_then(|(lead, vowel)| {
+            let lead = position(&LEADS, lead)?;
+            let vowel = position(&VOWELS, vowel)?;
+            let tail = match tail {
+                Some(tail) => position(&TAILS, tail)? + 1,
+                None => 0,
+            };
+            char::from_u32(FIRST_SYLLABLE + ((lead * 21 + vowel) * 28 + tail) as u32)
+        });
+        match syllable {
+            Some(syllable) => out.write_char(syllable),
+            None => lead
+                .into_iter()
+                .chain(vowel)
+                .chain(tail)
+                .try_for_each(|c| out.write_char(c)),
+        }
+    }
+
+    /// Returns `true` if jamo are held back.
+    pub(crate) const fn is_pending(&self) -> bool {
+        self.lead.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn decomposes_and_recomposes_syllables() {
+        let jamo = |c| decompose(c).map(|jamo| jamo.collect::<String>());
+        assert_eq!(jamo('한').as_deref(), Some("ㅎㅏㄴ"));
+        assert_eq!(jamo('뛟').as_deref(), Some("ㄷㄷㅜㅓㅣㄹㅂ"));
+        assert_eq!(jamo('ㅘ').as_deref(), Some("ㅗㅏ"));
+        assert_eq!(jamo('ㄱ'), None);
+        assert_eq!(jamo('A'), None);
+
+        let compose = |text: &str| {
+            let mut composer = HangulComposer::new();
+            let mut composed = String::new();
+            let jamo = text
+                .chars()
+                .flat_map(|c| decompose(c).map_or_else(|| [c].to_vec(), Iterator::collect))
+                .collect::<Vec<_>>();
+            for c in jamo {
+                composer.write_char(c, &mut composed).unwrap();
+            }
+            composer.flush(&mut composed).unwrap();
+            composed
+        };
+        for text in [
+            "한국",
+            "대한민국",
+            "읽어요",
+            "있다",
+            "학교",
+            "먹고",
+            "꼭꼭",
+            "작까",
+            "읽고",
+            "뛟",
+            "괜찮아요",
+            "ㅋㅋ",
+        ] {
+            assert_eq!(compose(text), text);
+        }
+        assert_eq!(compose("ㅏ"), "ㅏ");
+        assert_eq!(compose("아까"), "악가");
+    }
+}
@@ -0,0 +1,133 @@
+//! Conversion of Japanese kana to and from the forms Wabun code has codes for.
+//!
+//! Wabun code only covers full-size katakana without diacritics: voiced and semi-voiced kana are
+//! sent as their base kana followed by a dakuten (`゛`) or handakuten (`゜`), hiragana as katakana,
+//! and small kana as their full-size counterparts.
+
+use core::fmt;
+
+/// The spacing dakuten, which marks a voiced kana.
+const DAKUTEN: char = '゛';
+/// The spacing handakuten, which marks a semi-voiced kana.
+const HANDAKUTEN: char = '゜';
+
+/// Returns the kana and the optional mark Wabun code spells `c` with, or `None` if `c` is not a
+/// kana that needs converting.
+pub(crate) fn decompose(c: char) -> Option<(char, Option<char>)> {
+    let katakana = match c {
+        // Hiragana, which are laid out like katakana.
+        'ぁ'..='ゖ' => char::from_u32(c as u32 + 0x60)?,
+        '\u{3099}' => return Some((DAKUTEN, None)),
+        '\u{309A}' => return Some((HANDAKUTEN, None)),
+        _ => c,
+    };
+    let offset = |base: char, delta: u32| char::from_u32(base as u32 - delta);
+    let decomposed = match katakana {
+        'ガ' | 'ギ' | 'グ' | 'ゲ' | 'ゴ' | 'ザ' | 'ジ' | 'ズ' | 'ゼ' | 'ゾ' | 'ダ' | 'ヂ'
+        | 'ヅ' | 'デ' | 'ド' | 'バ' | 'ビ' | 'ブ' | 'ベ' | 'ボ' => {
+            (offset(katakana, 1)?, Some(DAKUTEN))
+        }
+        'パ' | 'ピ' | 'プ' | 'ペ' | 'ポ' => (offset(katakana, 2)?, Some(HANDAKUTEN)),
+        'ヴ' => ('ウ', Some(DAKUTEN)),
+        'ヷ' => ('ワ', Some(DAKUTEN)),
+        'ヸ' => ('ヰ', Some(DAKUTEN)),
+        'ヹ' => ('ヱ', Some(DAKUTEN)),
+        'ヺ' => ('ヲ', Some(DAKUTEN)),
+        'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' => {
+            (char::from_u32(katakana as u32 + 1)?, None)
+        }
+        'ヵ' => ('カ', None),
+        'ヶ' => ('ケ', None),
+        _ if katakana != c => (katakana, None),
+        _ => return None,
+    };
+    Some(decomposed)
+}
+
+/// Returns the voiced or semi-voiced kana made of the katakana `base` and the spacing `mark`.
+fn compose(base: char, mark: char) -> Option<char> {
+    let composed = match (base, mark) {
+        (
+            'カ' | 'キ' | 'ク' | 'ケ' | 'コ' | 'サ' | 'シ' | 'ス' | 'セ' | 'ソ' | 'タ' | 'チ'
+            | 'ツ' | 'テ' | 'ト' | 'ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ',
+            DAKUTEN,
+        ) => base as u32 + 1,
+        ('ハ' | 'ヒ' | 'フ' | 'ヘ' | 'ホ', HANDAKUTEN) => base as u32 + 2,
+        ('ウ', DAKUTEN) => 'ヴ' as u32,
+        ('ワ' | 'ヰ' | 'ヱ' | 'ヲ', DAKUTEN) => base as u32 + 8,
+        _ => return None,
+    };
+    char::from_u32(composed)
+}
+
+/// Recomposes decoded kana with the dakuten or handakuten that follows them.
+///
+/// A kana that may take a mark is held back until the next character, or until
+/// [`KanaComposer::flush`] is called.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct KanaComposer {
+    pending: Option<char>,
+}
+
+impl KanaComposer {
+    /// Creates a composer holding nothing back.
+    pub(crate) const fn new() -> Self {
+        Self { pending: None }
+    }
+
+    /// Writes `c` to `out`, composing it with the kana held back if possible.
+    pub(crate) fn write_char<W: fmt::Write>(&mut self, c: char, out: &mut W) -> fmt::Result {
+        if let Some(base) = self.pending.take() {
+            if let Some(composed) = compose(base, c) {
+                return out.write_char(composed);
+            }
+            out.write_char(base)?;
+        }
+        if compose(c, DAKUTEN).is_some() {
+            self.pending = Some(c);
+            Ok(())
+        } else {
+            out.write_char(c)
+        }
+    }
+
+    /// Writes the kana held back, if any.
+    pub(crate) fn flush<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        match self.pending.take() {
+            Some(c) => out.write_char(c),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` if a kana is held back.
+    pub(crate) const fn is_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn decomposes_and_recomposes_kana() {
+        assert_eq!(decompose('が'), Some(('カ', Some('゛'))));
+        assert_eq!(decompose('パ'), Some(('ハ', Some('゜'))));
+        assert_eq!(decompose('ゃ'), Some(('ヤ', None)));
+        assert_eq!(decompose('ッ'), Some(('ツ', None)));
+        assert_eq!(decompose('ヺ'), Some(('ヲ', Some('゛'))));
+        assert_eq!(decompose('\u{3099}'), Some(('゛', None)));
+        assert_eq!(decompose('カ'), None);
+        assert_eq!(decompose('A'), None);
+
+        let mut composer = KanaComposer::new();
+        let mut text = String::new();
+        for c in "カ゛ハ゜ヲ゛ア゛ウ".chars() {
+            composer.write_char(c, &mut text).unwrap();
+        }
+        assert!(composer.is_pending());
+        composer.flush(&mut text).unwrap();
+        assert_eq!(text, "ガパヺア゛ウ");
+    }
+}
@@ -9,6 +9,10 @@
 //! - **Decoding**: Convert Morse code back into readable text using the provided configuration.
 //! - **Customizable Character Sets**: Support for various character sets including Latin, Greek, Cyrillic, Arabic, and others.
 //! - **Configurable Options**: Define how Morse code should be represented with options for symbols and handling invalid characters.
+//! - **Custom Mappings**: Add, override or remove individual mappings, or register whole custom character sets, and load or save them as plain-text tables.
+//! - **Transliteration**: Optionally encode characters missing from the tables, such as `ő`, `Ё` or `“`, as their closest supported spelling, with every substitution reported.
+//! - **Script Detection**: Decode messages of unknown alphabet with every candidate character set, ranked by letter frequencies.
+//! - **Serialization**: Enable the `serde` feature to serialize and deserialize options, character sets and code tables.
 //!
 //! ## Usage
 //!
@@ -22,16 +26,21 @@
 //! Then, use the `MorseCode` struct to encode and decode text. Here’s a basic example:
 //!
 //! ```rust
-//! use morsify::{MorseCode, Options, MorseCharacterSet};
+//! use morsify::{InvalidCharPolicy, MorseCode, Options, MorseCharacterSet, UnknownSequencePolicy};
 //!
 //! // Create a new `MorseCode` instance with default options
 //! let options = Options {
-//!     dash: '-',
-//!     dot: '.',
-//!     space: '/',
-//!     separator: ' ',
-//!     invalid_char_callback: |c| c,
+//!     dash: "-".into(),
+//!     dot: ".".into(),
+//!     space: "/".into(),
+//!     separator: " ".into(),
+//!     invalid_char_policy: InvalidCharPolicy::Error,
+//!     transliterate: false,
+//!     unknown_sequence_policy: UnknownSequencePolicy::Error,
 //!     priority: MorseCharacterSet::Latin,
+//!     fallback: Vec::new().into(),
+//!     allowed: None,
+//!     wabun_switching: false,
 //! };
 //! let morse_code = MorseCode::new(options);
 //!
@@ -55,22 +64,59 @@
 #![no_std]
 
 use alloc::{
+    borrow::Cow,
     collections::btree_map::BTreeMap,
     string::{String, ToString},
+    vec::Vec,
 };
+use core::{fmt, ops::Range};
+
 extern crate alloc;
 
-/// A type alias for a map that associates Morse code characters with their string representations.
-///
-/// This alias defines a `BTreeMap` where:
-/// - The key is a `MorseCharacterSet`, which represents a specific set or category of Morse code characters.
-/// - The value is another `BTreeMap`, where:
-///   - The key is a `char` representing a single character.
-///   - The value is a `String` providing the string representation or description of the Morse code character.
-///
-/// This structure supports efficient storage and retrieval of Morse code characters and their associated string descriptions.
-/// The use of `BTreeMap` ensures that the data is kept in sorted order, enabling efficient lookups.
-type Characters = BTreeMap<MorseCharacterSet, BTreeMap<char, String>>;
+mod builder;
+mod charset;
+mod code_table;
+mod detect;
+mod element;
+mod error;
+mod hangul;
+mod kana;
+mod normalize;
+mod policy;
+mod script;
+mod sequence;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod stream;
+mod symbol;
+mod tables;
+mod translit;
+mod tree;
+
+pub use builder::OptionsBuilder;
+pub use charset::CharacterSet;
+use charset::SetRef;
+pub use code_table::CodeTable;
+pub use detect::Interpretation;
+pub use element::Element;
+pub use error::{
+    DecodeError, EncodeError, MappingError, OptionsError, ParseSequenceError, ParseTableError,
+    SymbolKind, TableErrorKind, UnrecognizedToken, UnsupportedChar,
+};
+use hangul::HangulComposer;
+use kana::KanaComposer;
+use policy::Replacement;
+use symbol::Symbol;
+use translit::transliterate;
+pub use translit::Substitution;
+
+pub use policy::{
+    InvalidCharHandler, InvalidCharPolicy, UnknownSequenceHandler, UnknownSequencePolicy,
+};
+pub use script::Script;
+pub use sequence::{MorseSequence, SequenceDisplay, SequenceIter};
+pub use stream::StreamDecoder;
+pub use tree::{MorseNode, MorseTree};
 
 /// Enumerates the different character sets used in Morse code.
 ///
@@ -91,6 +137,7 @@ type Characters = BTreeMap<MorseCharacterSet, BTreeMap<char, String>>;
 /// - [Thai Characters](https://th.wikipedia.org/wiki/รหัสมอร์ส)
 #[repr(usize)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MorseCharacterSet {
     /// Represents an undefined character set.
     Undefined,
@@ -120,43 +167,192 @@ pub enum MorseCharacterSet {
     Thai,
 }
 
+impl fmt::Display for MorseCharacterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
 /// Contains options for encoding and decoding Morse code.
 ///
 /// This struct allows customization of Morse code encoding and decoding by specifying the characters used
 /// for dashes, dots, spaces, separators, and invalid characters, as well as a priority character set.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// With the `serde` feature, options can be serialized and deserialized; missing fields take their
+/// default value, and deserialized options are checked with [`Options::validate`]. Policies that
+/// delegate to a custom handler cannot be serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serde_impls::UncheckedOptions")
+)]
 pub struct Options {
-    /// Character used to represent a dash in Morse code.
-    pub dash: char,
-    /// Character used to represent a dot in Morse code.
-    pub dot: char,
-    /// Character used to represent a space between words in Morse code.
-    pub space: char,
-    /// Character used to separate Morse code characters.
-    pub separator: char,
+    /// Symbol used to represent a dash in Morse code.
+    pub dash: Cow<'static, str>,
+    /// Symbol used to represent a dot in Morse code.
+    pub dot: Cow<'static, str>,
+    /// Symbol used to represent a space between words in Morse code.
+    ///
+    /// When encoding, the space is written between two separators.
+    pub space: Cow<'static, str>,
+    /// Symbol used to separate Morse code characters.
+    pub separator: Cow<'static, str>,
     /// Priority character set to use for encoding.
     pub priority: MorseCharacterSet,
-    /// A function used to get represented an invalid Morse code character.
-    pub invalid_char_callback: fn(char) -> char,
+    /// Character sets to look characters and codes up in after the priority set, in order, before
+    /// the remaining ones.
+    pub fallback: Cow<'static, [MorseCharacterSet]>,
+    /// The only built-in character sets to look characters and codes up in, or `None` to use them
+    /// all.
+    ///
+    /// A set missing from the list is ignored even if it is the priority set or a fallback set.
+    /// Custom character sets are always used.
+    pub allowed: Option<Cow<'static, [MorseCharacterSet]>>,
+    /// Whether to switch between international Morse code and Wabun code with the DO (`-..---`)
+    /// and SN (`...-.`) prosigns, as Japanese operators do.
+    ///
+    /// When encoding, DO is sent before a Japanese character that follows international ones, and
+    /// SN before an international character that follows Japanese ones. When decoding, the
+    /// Japanese character set takes priority between DO and SN, unless [`Options::allowed`]
+    /// excludes it. Characters whose code is one of the two prosigns cannot be encoded.
+    pub wabun_switching: bool,
+    /// Policy deciding how characters without a Morse code representation are encoded.
+    pub invalid_char_policy: InvalidCharPolicy,
+    /// Whether characters without a Morse code representation are first replaced by their closest
+    /// encodable spelling, such as `O` for `Ő`, `ZH` for `Ж` or `"` for `“`.
+    ///
+    /// Only characters without a known transliteration are left to the invalid character policy.
+    /// This is the same transliteration as [`InvalidCharPolicy::Transliterate`], which rejects the
+    /// characters it does not know; setting both behaves like the policy alone.
+    pub transliterate: bool,
+    /// Policy deciding how Morse code sequences that match no character are decoded.
+    pub unknown_sequence_policy: UnknownSequencePolicy,
 }
 
-impl Default for Options {
-    fn default() -> Self {
+impl Options {
+    /// Returns the default options, in a `const` context.
+    ///
+    /// Dots and dashes are written as `.` and `-`, words are separated by `/` and characters by a
+    /// space, and the Latin character set takes priority.
+    #[must_use]
+    pub const fn new() -> Self {
         Self {
-            dash: '-',
-            dot: '.',
-            space: '/',
-            separator: ' ',
-            invalid_char_callback: |c| c,
+            dash: Cow::Borrowed("-"),
+            dot: Cow::Borrowed("."),
+            space: Cow::Borrowed("/"),
+            separator: Cow::Borrowed(" "),
+            invalid_char_policy: InvalidCharPolicy::Error,
+            transliterate: false,
+            unknown_sequence_policy: UnknownSequencePolicy::Error,
             priority: MorseCharacterSet::Latin,
+            fallback: Cow::Borrowed(&[]),
+            allowed: None,
+            wabun_switching: false,
+        }
+    }
+
+    /// Returns a builder starting from the default options, which validates them when built.
+    pub const fn builder() -> OptionsBuilder {
+        OptionsBuilder::new()
+    }
+
+    /// Checks that the symbols of these options can be told apart when decoding.
+    ///
+    /// The dot, dash, space and separator must all be non-empty and differ from one another, and
+    /// the separator must not be a single character of the built-in tables. The space and the
+    /// separator must not be made of dots and dashes. Symbols may share prefixes, as the longest
+    /// matching symbol wins when decoding, but only if the longer symbol cannot also be read as the
+    /// shorter one followed by others: a dot of `a` and a dash of `aa` are rejected, while a dot of
+    /// `.` and a dash of `.-` are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OptionsError`] describing the first symbol collision or ambiguity found.
+    pub fn validate(&self) -> Result<(), OptionsError> {
+        let symbols = [
+            (SymbolKind::Dot, &self.dot),
+            (SymbolKind::Dash, &self.dash),
+            (SymbolKind::Space, &self.space),
+            (SymbolKind::Separator, &self.separator),
+        ];
+
+        if let Some(&(kind, _)) = symbols.iter().find(|(_, s)| s.is_empty()) {
+            return Err(OptionsError::EmptySymbol { kind });
+        }
+        for (i, &(first, symbol)) in symbols.iter().enumerate() {
+            if let Some(&(second, _)) = symbols[i + 1..].iter().find(|&&(_, s)| s == symbol) {
+                return Err(OptionsError::SameSymbol {
+                    first,
+                    second,
+                    symbol: symbol.to_string(),
+                });
+            }
+        }
+        let mut separator = self.separator.chars();
+        if let (Some(c), None) = (separator.next(), separator.next()) {
+            if let Some(set) = tables::ALL
+                .into_iter()
+                .find(|&set| tables::table(set).is_some_and(|table| table.code(c).is_some()))
+            {
+                return Err(OptionsError::TableCharacter {
+                    kind: SymbolKind::Separator,
+                    symbol: c,
+                    set,
+                });
+            }
         }
+        let elements = [&*self.dot, &*self.dash];
+        for &(kind, symbol) in &symbols[2..] {
+            if symbol::is_sequence_of(symbol, &elements) {
+                return Err(OptionsError::SpelledWithElements {
+                    kind,
+                    symbol: symbol.to_string(),
+                });
+            }
+        }
+        // Consecutive gaps decode as one, so only a confusion involving a dot or a dash matters.
+        let texts = symbols.map(|(_, symbol)| &**symbol);
+        for &(first, prefix) in &symbols {
+            for &(second, symbol) in &symbols {
+                let is_gap = |kind| matches!(kind, SymbolKind::Space | SymbolKind::Separator);
+                let rest = symbol
+                    .strip_prefix(&**prefix)
+                    .filter(|rest| !rest.is_empty());
+                if !(is_gap(first) && is_gap(second))
+                    && rest.is_some_and(|rest| symbol::begins_sequence(rest, &texts))
+                {
+                    return Err(OptionsError::AmbiguousSymbols { first, second });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 /// A struct to manage Morse code operations including encoding and decoding.
 ///
-/// This struct holds options and character mappings required for encoding and decoding Morse code.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// This struct holds the options required for encoding and decoding Morse code. The character
+/// mappings themselves are static tables, so creating a `MorseCode` costs nothing and can even be
+/// done in a `const` or `static` item.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{MorseCode, Options};
+///
+/// static MORSE_CODE: MorseCode = MorseCode::new(Options::new());
+///
+/// assert_eq!(MORSE_CODE.encode("SOS"), "... --- ...");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MorseCode {
     /// Configuration options for encoding and decoding Morse code.
     ///
@@ -164,21 +360,16 @@ pub struct MorseCode {
     /// spaces, separators, and handling invalid characters. It also defines the priority character set to use.
     options: Options,
 
-    /// A map of Morse code characters and their string representations.
-    ///
-    /// This field contains a `BTreeMap` where each key is a `MorseCharacterSet` indicating a specific set or category
-    /// of Morse code characters, and each value is another `BTreeMap` mapping individual Morse code characters to their
-    /// string representations or descriptions. This structure supports efficient storage and retrieval of Morse code data.
-    characters: Characters,
+    /// Built-in character sets replaced by user-defined ones, searched in place of their table.
+    replaced: BTreeMap<MorseCharacterSet, CharacterSet>,
+
+    /// Custom character sets, searched before every other set in the order they were added.
+    custom: Vec<CharacterSet>,
 }
 
 impl Default for MorseCode {
     fn default() -> Self {
-        let options = Options::default();
-        Self {
-            options,
-            characters: get_characters(options),
-        }
+        Self::new(Options::default())
     }
 }
 
@@ -193,16 +384,126 @@ impl MorseCode {
     ///
     /// A `MorseCode` instance configured with the provided options.
     #[must_use]
-    pub fn new(options: Options) -> Self {
-        let characters = get_characters(options);
+    pub const fn new(options: Options) -> Self {
+        MorseCode {
+            options,
+            replaced: BTreeMap::new(),
+            custom: Vec::new(),
+        }
+    }
+
+    /// Creates a new `MorseCode` instance with the given options, after checking them with
+    /// [`Options::validate`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OptionsError`] if the options cannot be decoded unambiguously.
+    pub fn try_new(options: Options) -> Result<Self, OptionsError> {
+        options.validate()?;
+        Ok(Self::new(options))
+    }
+
+    /// Creates a `MorseCode` instance whose only mappings are those of the given table.
+    ///
+    /// Built-in character sets missing from the table have no mappings at all.
+    #[must_use]
+    pub fn from_code_table(options: Options, table: CodeTable) -> Self {
+        let mut sets = table.sets;
+        let replaced = tables::ALL
+            .into_iter()
+            .map(|set| (set, sets.remove(&set).unwrap_or_default()))
+            .collect();
         MorseCode {
             options,
-            characters,
+            replaced,
+            custom: table.custom,
+        }
+    }
+
+    /// Returns every mapping of this `MorseCode`, to be written as a plain-text table.
+    ///
+    /// Built-in character sets are included with their current mappings, unless they have none.
+    #[must_use]
+    pub fn code_table(&self) -> CodeTable {
+        let sets = tables::ALL
+            .into_iter()
+            .map(|set| {
+                let characters = self
+                    .replaced
+                    .get(&set)
+                    .cloned()
+                    .unwrap_or_else(|| CharacterSet::from(set));
+                (set, characters)
+            })
+            .filter(|(_, characters)| !characters.is_empty())
+            .collect();
+        CodeTable {
+            sets,
+            custom: self.custom.clone(),
         }
     }
 
+    /// Adds a custom character set.
+    ///
+    /// Custom sets are searched before every built-in set, including the priority one, in the
+    /// order they were added. Their characters are therefore encoded with their custom code, and
+    /// their codes decode to them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{CharacterSet, MorseCode, MorseSequence};
+    ///
+    /// let mut abbreviations = CharacterSet::new();
+    /// abbreviations.insert('✓', MorseSequence::from_code("-.-.-"))?;
+    ///
+    /// let mut morse_code = MorseCode::default();
+    /// morse_code.add_character_set(abbreviations);
+    ///
+    /// assert_eq!(morse_code.encode("OK ✓"), "--- -.- / -.-.-");
+    /// assert_eq!(morse_code.decode("-.-.-"), "✓");
+    /// # Ok::<(), morsify::MappingError>(())
+    /// ```
+    pub fn add_character_set(&mut self, set: CharacterSet) {
+        self.custom.push(set);
+    }
+
+    /// Replaces the mappings of a built-in character set with the given ones.
+    ///
+    /// The set keeps its place in the lookup order. Replacing [`MorseCharacterSet::Undefined`],
+    /// which has no built-in mappings, gives it some.
+    pub fn replace_character_set(&mut self, set: MorseCharacterSet, characters: CharacterSet) {
+        self.replaced.insert(set, characters);
+    }
+
+    /// Returns the mappings of a built-in character set for editing, copying its built-in table on
+    /// first use.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCharacterSet, MorseCode, MorseSequence};
+    ///
+    /// let mut morse_code = MorseCode::default();
+    /// let punctuation = morse_code.character_set_mut(MorseCharacterSet::Punctuation);
+    /// punctuation.insert('×', MorseSequence::from_code("-..-"))?;
+    /// punctuation.remove('$');
+    ///
+    /// assert_eq!(morse_code.encode("2×2"), "..--- -..- ..---");
+    /// assert_eq!(morse_code.code('$'), None);
+    /// # Ok::<(), morsify::MappingError>(())
+    /// ```
+    pub fn character_set_mut(&mut self, set: MorseCharacterSet) -> &mut CharacterSet {
+        self.replaced
+            .entry(set)
+            .or_insert_with(|| CharacterSet::from(set))
+    }
+
     /// Encodes the given text into Morse code using the struct’s options.
     ///
+    /// The text is normalized first, so that decomposed accents in any order, fullwidth letters,
+    /// halfwidth katakana and superscript digits encode like their plain, precomposed forms.
+    ///
     /// # Arguments
     ///
     /// * `text` - The text to encode.
@@ -212,753 +513,896 @@ impl MorseCode {
     /// A `String` containing the encoded Morse code.
     pub fn encode<S: AsRef<str>>(&self, text: S) -> String {
         let mut result = String::new();
+        self.encode_with(text.as_ref(), &mut result, |_| {}, |_, _, _| {})
+            .expect("writing to a `String` cannot fail");
+        result
+    }
 
-        let processed_text = text
-            .as_ref()
-            .replace(char::is_whitespace, &self.options.separator.to_string())
-            .trim()
-            .to_uppercase();
-
-        for character in processed_text.chars() {
-            let mut found = false;
-            for set in self.characters.values() {
-                if let Some(encoded) = set.get(&character) {
-                    result.push_str(encoded);
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
-                (self.options.invalid_char_callback)(character);
-                result.push((self.options.invalid_char_callback)(character));
-            }
-            result.push(self.options.separator);
-        }
+    /// Encodes the given text into Morse code, writing the result straight to `out`.
+    ///
+    /// Apart from what a custom [`InvalidCharPolicy`] may allocate, this method performs no heap
+    /// allocation, which makes it suitable for constrained targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to encode.
+    /// * `out` - The sink the Morse code is written to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn encode_to<S: AsRef<str>, W: fmt::Write>(&self, text: S, out: &mut W) -> fmt::Result {
+        self.encode_with(text.as_ref(), out, |_| {}, |_, _, _| {})
+    }
 
-        result = result
-            .replace('0', &self.options.dot.to_string())
-            .replace('1', &self.options.dash.to_string());
+    /// Encodes the given text into Morse code, failing on any character that has no Morse code representation.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to encode.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the encoded Morse code.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`EncodeError`] listing every unsupported character, together with its index in
+    /// `text` and its Unicode script.
+    pub fn try_encode<S: AsRef<str>>(&self, text: S) -> Result<String, EncodeError> {
+        let mut result = String::new();
+        let mut unsupported = Vec::new();
+        self.encode_with(
+            text.as_ref(),
+            &mut result,
+            |c| unsupported.push(c),
+            |_, _, _| {},
+        )
+        .expect("writing to a `String` cannot fail");
 
-        if !result.is_empty() && result.ends_with(&self.options.separator.to_string()) {
-            result.pop();
+        if unsupported.is_empty() {
+            Ok(result)
+        } else {
+            Err(EncodeError::new(unsupported))
         }
-
-        result
     }
 
-    /// Decodes the given Morse code string into text using the struct’s options.
+    /// Encodes the given text into Morse code, reporting every character that was encoded as a
+    /// replacement, either by transliteration or by the invalid character policy.
     ///
     /// # Arguments
     ///
-    /// * `morse` - The Morse code string to decode.
+    /// * `text` - The text to encode.
     ///
     /// # Returns
     ///
-    /// A `String` containing the decoded text.
-    pub fn decode(&self, morse: &str) -> String {
-        let swapped = swap_characters(self.options);
-
-        morse
-            .replace(char::is_whitespace, &self.options.separator.to_string()) // Replace whitespace with separator
-            .trim() // Trim leading and trailing whitespace
-            .split(self.options.separator) // Split by the separator
-            .map(|characters| {
-                swapped
-                    .get(characters)
-                    .copied()
-                    .map_or_else(|| characters.to_string(), |c| c.to_string())
-            })
-            .collect::<String>() // Collect into a single String
+    /// A `String` containing the encoded Morse code, and the substitutions made in the order they
+    /// appear in `text`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCode, Options, Substitution};
+    ///
+    /// let morse_code = MorseCode::new(Options {
+    ///     transliterate: true,
+    ///     ..Default::default()
+    /// });
+    /// let (encoded, substitutions) = morse_code.encode_with_substitutions("Dvořák");
+    ///
+    /// assert_eq!(encoded, "-.. ...- --- .-. .--.- -.-");
+    /// assert_eq!(
+    ///     substitutions,
+    ///     [Substitution {
+    ///         character: 'ř',
+    ///         index: 3,
+    ///         replacement: "R".into(),
+    ///     }]
+    /// );
+    /// ```
+    pub fn encode_with_substitutions<S: AsRef<str>>(&self, text: S) -> (String, Vec<Substitution>) {
+        let mut result = String::new();
+        let mut substitutions = Vec::new();
+        self.encode_with(
+            text.as_ref(),
+            &mut result,
+            |_| {},
+            |index, character, replacement| {
+                substitutions.push(Substitution {
+                    character,
+                    index,
+                    replacement: replacement.to_string(),
+                });
+            },
+        )
+        .expect("writing to a `String` cannot fail");
+        (result, substitutions)
     }
-}
-
-/// Generates a complete set of Morse code characters for various languages and symbols.
-///
-/// This function creates and returns a `Characters` mapping that includes Morse code representations
-/// for Latin, numbers, punctuation, Latin extended characters, Cyrillic, Greek, Hebrew, Arabic,
-/// Persian, Japanese, Korean, and Thai characters. Each set of characters is stored in a `BTreeMap`
-/// and organized by the type of Morse character set.
-fn base_characters() -> Characters {
-    let mut characters = BTreeMap::new();
-    let latin = latin_chars();
-    characters.insert(MorseCharacterSet::Latin, latin);
-
-    let numbers = numbers_chars();
-    characters.insert(MorseCharacterSet::Numbers, numbers);
-
-    let punctuation = punctuation_chars();
-    characters.insert(MorseCharacterSet::Punctuation, punctuation);
-
-    let latin_extended = latin_extended_chars();
-    characters.insert(MorseCharacterSet::LatinExtended, latin_extended);
-
-    let cyrillic = cyrillic_chars();
-    characters.insert(MorseCharacterSet::Cyrillic, cyrillic);
-
-    let greek = greek_chars();
-    characters.insert(MorseCharacterSet::Greek, greek);
-
-    let hebrew = hebrew_chars();
-    characters.insert(MorseCharacterSet::Hebrew, hebrew);
-
-    let arabic = arabic_chars();
-    characters.insert(MorseCharacterSet::Arabic, arabic);
 
-    let persian = persian_chars();
-    characters.insert(MorseCharacterSet::Persian, persian);
-
-    let japanese = japanese_chars();
-    characters.insert(MorseCharacterSet::Japanese, japanese);
-
-    let korean = korean_chars();
-    characters.insert(MorseCharacterSet::Korean, korean);
-
-    let thai = thai_chars();
-    characters.insert(MorseCharacterSet::Thai, thai);
+    /// Returns the elements to key for the given text, one at a time.
+    ///
+    /// Each element is paired with the index, counted in `char`s, of the character of `text` it was
+    /// produced for. Dots, dashes and the gaps between them belong to their character, a character
+    /// gap belongs to the character that follows it, and a word gap to the whitespace that produced
+    /// it. Characters are looked up exactly as [`MorseCode::encode`] does, including the invalid
+    /// character policy; characters left without a code produce no elements.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{Element, MorseCode};
+    ///
+    /// let morse_code = MorseCode::default();
+    /// let elements = morse_code.elements("A T").collect::<Vec<_>>();
+    ///
+    /// assert_eq!(
+    ///     elements,
+    ///     [
+    ///         (0, Element::Dot),
+    ///         (0, Element::IntraCharGap),
+    ///         (0, Element::Dash),
+    ///         (1, Element::WordGap),
+    ///         (2, Element::Dash),
+    ///     ]
+    /// );
+    /// ```
+    pub fn elements<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (usize, Element)> + 'a {
+        let mut is_first = true;
+        let mut word_gap = None;
 
-    characters
-        .into_iter()
-        .map(|(charset, char_map)| {
-            (
-                charset,
-                char_map
-                    .into_iter()
-                    .map(|(char, morse_code)| (char, morse_code.to_string()))
-                    .collect(),
-            )
+        self.units(text).flat_map(move |(index, _, unit)| {
+            let (gap, code) = match unit {
+                Unit::Code(code) => {
+                    let gap =
+                        (!is_first).then(|| word_gap.take().unwrap_or((index, Element::CharGap)));
+                    is_first = false;
+                    (gap, code)
+                }
+                Unit::WordGap if !is_first => {
+                    word_gap.get_or_insert((index, Element::WordGap));
+                    (None, MorseSequence::EMPTY)
+                }
+                Unit::WordGap | Unit::Unsupported(_) | Unit::Substituted(_) => {
+                    (None, MorseSequence::EMPTY)
+                }
+            };
+            let signals = code.iter().enumerate().flat_map(move |(i, element)| {
+                let intra_char_gap = (i > 0).then_some((index, Element::IntraCharGap));
+                intra_char_gap.into_iter().chain([(index, element)])
+            });
+            gap.into_iter().chain(signals)
         })
-        .collect::<Characters>()
-}
-/// Returns a `BTreeMap` of Morse code representations for Latin characters.
-///
-/// This function provides the Morse code mappings for Latin alphabet characters, where each key is a Latin character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Latin characters as keys and their Morse code representations as values.
-fn latin_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut latin = BTreeMap::new();
-    latin.insert('A', "01");
-    latin.insert('B', "1000");
-    latin.insert('C', "1010");
-    latin.insert('D', "100");
-    latin.insert('E', "0");
-    latin.insert('F', "0010");
-    latin.insert('G', "110");
-    latin.insert('H', "0000");
-    latin.insert('I', "00");
-    latin.insert('J', "0111");
-    latin.insert('K', "101");
-    latin.insert('L', "0100");
-    latin.insert('M', "11");
-    latin.insert('N', "10");
-    latin.insert('O', "111");
-    latin.insert('P', "0110");
-    latin.insert('Q', "1101");
-    latin.insert('R', "010");
-    latin.insert('S', "000");
-    latin.insert('T', "1");
-    latin.insert('U', "001");
-    latin.insert('V', "0001");
-    latin.insert('W', "011");
-    latin.insert('X', "1001");
-    latin.insert('Y', "1011");
-    latin.insert('Z', "1100");
-    latin
-}
-
-/// Returns a `BTreeMap` of Morse code representations for digits (0-9).
-///
-/// This function provides the Morse code mappings for numeric digits, where each key is a digit and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with digits as keys and their Morse code representations as values.
-fn numbers_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut numbers = BTreeMap::new();
-    numbers.insert('0', "11111");
-    numbers.insert('1', "01111");
-    numbers.insert('2', "00111");
-    numbers.insert('3', "00011");
-    numbers.insert('4', "00001");
-    numbers.insert('5', "00000");
-    numbers.insert('6', "10000");
-    numbers.insert('7', "11000");
-    numbers.insert('8', "11100");
-    numbers.insert('9', "11110");
-    numbers
-}
-
-/// Returns a `BTreeMap` of Morse code representations for punctuation marks.
-///
-/// This function provides the Morse code mappings for various punctuation marks, where each key is a punctuation mark
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with punctuation marks as keys and their Morse code representations as values.
-fn punctuation_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut punctuation = BTreeMap::new();
-    punctuation.insert('.', "010101");
-    punctuation.insert(',', "110011");
-    punctuation.insert('?', "001100");
-    punctuation.insert('\'', "011110");
-    punctuation.insert('!', "101011");
-    punctuation.insert('/', "10010");
-    punctuation.insert('(', "10110");
-    punctuation.insert(')', "101101");
-    punctuation.insert('&', "01000");
-    punctuation.insert(':', "111000");
-    punctuation.insert(';', "101010");
-    punctuation.insert('=', "10001");
-    punctuation.insert('+', "01010");
-    punctuation.insert('-', "100001");
-    punctuation.insert('_', "001101");
-    punctuation.insert('"', "010010");
-    punctuation.insert('$', "0001001");
-    punctuation.insert('@', "011010");
-    punctuation.insert('¿', "00101");
-    punctuation.insert('¡', "110001");
-    punctuation
-}
-
-/// Returns a `BTreeMap` of Morse code representations for Latin Extended characters.
-///
-/// This function provides the Morse code mappings for Latin Extended characters, where each key is a Latin Extended character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Latin Extended characters as keys and their Morse code representations as values.
-fn latin_extended_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut latin_extended = BTreeMap::new();
-    latin_extended.insert('Ã', "01101");
-    latin_extended.insert('Á', "01101");
-    latin_extended.insert('Å', "01101");
-    latin_extended.insert('À', "01101");
-    latin_extended.insert('Â', "01101");
-    latin_extended.insert('Ä', "0101");
-    latin_extended.insert('Ą', "0101");
-    latin_extended.insert('Æ', "0101");
-    latin_extended.insert('Ç', "10100");
-    latin_extended.insert('Ć', "10100");
-    latin_extended.insert('Ĉ', "10100");
-    latin_extended.insert('Č', "110");
-    latin_extended.insert('Ð', "00110");
-    latin_extended.insert('È', "01001");
-    latin_extended.insert('Ę', "00100");
-    latin_extended.insert('Ë', "00100");
-    latin_extended.insert('É', "00100");
-    latin_extended.insert('Ê', "10010");
-    latin_extended.insert('Ğ', "11010");
-    latin_extended.insert('Ĝ', "11010");
-    latin_extended.insert('Ĥ', "1111");
-    latin_extended.insert('İ', "01001");
-    latin_extended.insert('Ï', "10011");
-    latin_extended.insert('Ì', "01110");
-    latin_extended.insert('Ĵ', "01110");
-    latin_extended.insert('Ł', "01001");
-    latin_extended.insert('Ń', "11011");
-    latin_extended.insert('Ñ', "11011");
-    latin_extended.insert('Ó', "1110");
-    latin_extended.insert('Ò', "1110");
-    latin_extended.insert('Ö', "1110");
-    latin_extended.insert('Ô', "1110");
-    latin_extended.insert('Ø', "1110");
-    latin_extended.insert('Ś', "0001000");
-    latin_extended.insert('Ş', "01100");
-    latin_extended.insert('Ș', "1111");
-    latin_extended.insert('Š', "1111");
-    latin_extended.insert('Ŝ', "00010");
-    latin_extended.insert('ß', "000000");
-    latin_extended.insert('Þ', "01100");
-    latin_extended.insert('Ü', "0011");
-    latin_extended.insert('Ù', "0011");
-    latin_extended.insert('Ŭ', "0011");
-    latin_extended.insert('Ž', "11001");
-    latin_extended.insert('Ź', "110010");
-    latin_extended.insert('Ż', "11001");
-    latin_extended
-}
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Cyrillic characters.
-///
-/// This function provides the Morse code mappings for Cyrillic alphabet characters, where each key is a Cyrillic character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Cyrillic characters as keys and their Morse code representations as values.
-fn cyrillic_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut cyrillic = BTreeMap::new();
-    cyrillic.insert('А', "01");
-    cyrillic.insert('Б', "1000");
-    cyrillic.insert('В', "011");
-    cyrillic.insert('Г', "110");
-    cyrillic.insert('Д', "100");
-    cyrillic.insert('Е', "0");
-    cyrillic.insert('Ж', "0001");
-    cyrillic.insert('З', "1100");
-    cyrillic.insert('И', "00");
-    cyrillic.insert('Й', "0111");
-    cyrillic.insert('К', "101");
-    cyrillic.insert('Л', "0100");
-    cyrillic.insert('М', "11");
-    cyrillic.insert('Н', "10");
-    cyrillic.insert('О', "111");
-    cyrillic.insert('П', "0110");
-    cyrillic.insert('Р', "010");
-    cyrillic.insert('С', "000");
-    cyrillic.insert('Т', "1");
-    cyrillic.insert('У', "001");
-    cyrillic.insert('Ф', "0010");
-    cyrillic.insert('Х', "0000");
-    cyrillic.insert('Ц', "1010");
-    cyrillic.insert('Ч', "1110");
-    cyrillic.insert('Ш', "1111");
-    cyrillic.insert('Щ', "1101");
-    cyrillic.insert('Ъ', "11011");
-    cyrillic.insert('Ы', "1011");
-    cyrillic.insert('Ь', "1001");
-    cyrillic.insert('Э', "00100");
-    cyrillic.insert('Ю', "0011");
-    cyrillic.insert('Я', "0101");
-    cyrillic.insert('Ї', "01110");
-    cyrillic.insert('Є', "00100");
-    cyrillic.insert('І', "00");
-    cyrillic.insert('Ґ', "110");
-    cyrillic
-}
+    /// Splits `text` into the units it is encoded as, applying the invalid character policy.
+    ///
+    /// Each unit is returned with the index, counted in `char`s, of the character of `text` it was
+    /// produced for, and the value of that character after normalization. Leading and trailing
+    /// whitespace is ignored.
+    fn units<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (usize, char, Unit)> + 'a {
+        let leading = text.chars().take_while(|c| c.is_whitespace()).count();
+        let chars = text
+            .trim()
+            .chars()
+            .enumerate()
+            .map(move |(index, c)| (leading + index, c));
 
-/// Returns a `BTreeMap` of Morse code representations for Greek characters.
-///
-/// This function provides the Morse code mappings for Greek alphabet characters, where each key is a Greek character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Greek characters as keys and their Morse code representations as values.
-fn greek_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut greek = BTreeMap::new();
-    greek.insert('Α', "01");
-    greek.insert('Β', "1000");
-    greek.insert('Γ', "110");
-    greek.insert('Δ', "100");
-    greek.insert('Ε', "0");
-    greek.insert('Ζ', "1100");
-    greek.insert('Η', "0000");
-    greek.insert('Θ', "1010");
-    greek.insert('Ι', "00");
-    greek.insert('Κ', "101");
-    greek.insert('Λ', "0100");
-    greek.insert('Μ', "11");
-    greek.insert('Ν', "10");
-    greek.insert('Ξ', "1001");
-    greek.insert('Ο', "111");
-    greek.insert('Π', "0110");
-    greek.insert('Ρ', "010");
-    greek.insert('Σ', "000");
-    greek.insert('Τ', "1");
-    greek.insert('Υ', "1011");
-    greek.insert('Φ', "0010");
-    greek.insert('Χ', "1111");
-    greek.insert('Ψ', "1101");
-    greek.insert('Ω', "011");
-    greek
-}
+        let mut wabun = false;
 
-/// Returns a `BTreeMap` of Morse code representations for Hebrew characters.
-///
-/// This function provides the Morse code mappings for Hebrew script characters, where each key is a Hebrew character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Hebrew characters as keys and their Morse code representations as values.
-fn hebrew_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut hebrew = BTreeMap::new();
-    hebrew.insert('א', "01");
-    hebrew.insert('ב', "1000");
-    hebrew.insert('ג', "110");
-    hebrew.insert('ד', "100");
-    hebrew.insert('ה', "111");
-    hebrew.insert('ו', "0");
-    hebrew.insert('ז', "1100");
-    hebrew.insert('ח', "0000");
-    hebrew.insert('ט', "001");
-    hebrew.insert('י', "00");
-    hebrew.insert('כ', "101");
-    hebrew.insert('ל', "0100");
-    hebrew.insert('מ', "11");
-    hebrew.insert('נ', "10");
-    hebrew.insert('ס', "1010");
-    hebrew.insert('ע', "0111");
-    hebrew.insert('פ', "0110");
-    hebrew.insert('צ', "011");
-    hebrew.insert('ק', "1101");
-    hebrew.insert('ר', "010");
-    hebrew.insert('ש', "000");
-    hebrew.insert('ת', "1");
-    hebrew
-}
+        normalize::normalize(chars)
+            .flat_map(move |(index, original)| {
+                let word_gap = original.is_whitespace().then_some((Unit::WordGap, None));
+                let characters = (!original.is_whitespace())
+                    .then(|| original.to_uppercase())
+                    .into_iter()
+                    .flatten()
+                    // Kana without a code of their own are spelled as Wabun code spells them.
+                    .flat_map(move |c| {
+                        match kana::decompose(c).filter(|_| self.encodable(c).is_none()) {
+                            Some((base, mark)) => [Some(base), mark],
+                            None => [Some(c), None],
+                        }
+                        .into_iter()
+                        .flatten()
+                    })
+                    // Hangul without a code of its own is spelled in jamo as SKATS spells it.
+                    .flat_map(move |c| {
+                        let jamo = hangul::decompose(c).filter(|_| self.encodable(c).is_none());
+                        let plain = jamo.is_none().then_some(c);
+                        plain.into_iter().chain(jamo.into_iter().flatten())
+                    })
+                    .flat_map(move |character| {
+                        let code = self.encodable(character);
+                        let (substituted, replacement) = code
+                            .is_none()
+                            .then(|| match self.substitute(character) {
+                                Some(replacement) => {
+                                    (Some(Unit::Substituted(replacement.clone())), replacement)
+                                }
+                                // A rejected character is emitted unchanged, as it has no code of
+                                // its own.
+                                None => (None, Replacement::Char(character)),
+                            })
+                            .unzip();
+                        let replaced = replacement
+                            .into_iter()
+                            .flatten()
+                            .flat_map(char::to_uppercase)
+                            .map(|c| match self.encodable(c) {
+                                Some(code) => (Unit::Code(code), self.mode(c)),
+                                None => (Unit::Unsupported(c), None),
+                            });
+                        code.map(|code| (Unit::Code(code), self.mode(character)))
+                            .into_iter()
+                            .chain(substituted.flatten().map(|unit| (unit, None)))
+                            .chain(replaced)
+                    });
+                word_gap
+                    .into_iter()
+                    .chain(characters)
+                    .map(move |(unit, mode)| (index, original, unit, mode))
+            })
+            .flat_map(move |(index, original, unit, mode)| {
+                // Switch modes before the first character of the other one.
+                let switch = mode.filter(|&mode| mode != wabun).map(|mode| {
+                    wabun = mode;
+                    Unit::Code(if mode {
+                        tables::WABUN_START
+                    } else {
+                        tables::WABUN_END
+                    })
+                });
+                switch
+                    .into_iter()
+                    .chain([unit])
+                    .map(move |unit| (index, original, unit))
+            })
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Arabic characters.
-///
-/// This function provides the Morse code mappings for Arabic script characters, where each key is an Arabic character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Arabic characters as keys and their Morse code representations as values.
-fn arabic_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut arabic = BTreeMap::new();
-    arabic.insert('ا', "01");
-    arabic.insert('ب', "1000");
-    arabic.insert('ت', "1");
-    arabic.insert('ث', "1010");
-    arabic.insert('ج', "0111");
-    arabic.insert('ح', "0000");
-    arabic.insert('خ', "111");
-    arabic.insert('د', "100");
-    arabic.insert('ذ', "1100");
-    arabic.insert('ر', "010");
-    arabic.insert('ز', "1110");
-    arabic.insert('س', "000");
-    arabic.insert('ش', "1111");
-    arabic.insert('ص', "1001");
-    arabic.insert('ض', "0001");
-    arabic.insert('ط', "001");
-    arabic.insert('ظ', "1011");
-    arabic.insert('ع', "0101");
-    arabic.insert('غ', "110");
-    arabic.insert('ف', "0010");
-    arabic.insert('ق', "1101");
-    arabic.insert('ك', "101");
-    arabic.insert('ل', "0100");
-    arabic.insert('م', "11");
-    arabic.insert('ن', "10");
-    arabic.insert('ه', "00100");
-    arabic.insert('و', "011");
-    arabic.insert('ي', "00");
-    arabic.insert('ﺀ', "0");
-    arabic
-}
+    /// Returns the code `c` is encoded as, leaving out the codes reserved for switching between
+    /// Wabun and international Morse code when [`Options::wabun_switching`] is set.
+    fn encodable(&self, c: char) -> Option<MorseSequence> {
+        self.lookup(c).filter(|&code| {
+            !self.options.wabun_switching
+                || code != tables::WABUN_START && code != tables::WABUN_END
+        })
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Persian characters.
-///
-/// This function provides the Morse code mappings for Persian script characters, where each key is a Persian character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Persian characters as keys and their Morse code representations as values.
-fn persian_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut persian = BTreeMap::new();
-    persian.insert('ا', "01");
-    persian.insert('ب', "1000");
-    persian.insert('پ', "0110");
-    persian.insert('ت', "1");
-    persian.insert('ث', "1010");
-    persian.insert('ج', "0111");
-    persian.insert('چ', "1110");
-    persian.insert('ح', "0000");
-    persian.insert('خ', "1001");
-    persian.insert('د', "100");
-    persian.insert('ذ', "0001");
-    persian.insert('ر', "010");
-    persian.insert('ز', "1100");
-    persian.insert('ژ', "110");
-    persian.insert('س', "000");
-    persian.insert('ش', "1111");
-    persian.insert('ص', "0101");
-    persian.insert('ض', "00100");
-    persian.insert('ط', "001");
-    persian.insert('ظ', "1011");
-    persian.insert('ع', "111");
-    persian.insert('غ', "0011");
-    persian.insert('ف', "0010");
-    persian.insert('ق', "111000");
-    persian.insert('ک', "101");
-    persian.insert('گ', "1101");
-    persian.insert('ل', "0100");
-    persian.insert('م', "11");
-    persian.insert('ن', "10");
-    persian.insert('و', "011");
-    persian.insert('ه', "0");
-    persian.insert('ی', "00");
-    persian
-}
+    /// Returns whether `c` is to be sent in Wabun mode, or `None` if modes are not switched.
+    fn mode(&self, c: char) -> Option<bool> {
+        self.options
+            .wabun_switching
+            .then(|| self.wabun_set().is_some_and(|set| set.code(c).is_some()))
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Japanese characters.
-///
-/// This function provides the Morse code mappings for Japanese Katakana characters, where each key is a Japanese character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Japanese characters as keys and their Morse code representations as values.
-fn japanese_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut japanese = BTreeMap::new();
-    japanese.insert('ア', "11011");
-    japanese.insert('カ', "0100");
-    japanese.insert('サ', "10101");
-    japanese.insert('タ', "10");
-    japanese.insert('ナ', "010");
-    japanese.insert('ハ', "1000");
-    japanese.insert('マ', "1001");
-    japanese.insert('ヤ', "011");
-    japanese.insert('ラ', "000");
-    japanese.insert('ワ', "101");
-    japanese.insert('イ', "01");
-    japanese.insert('キ', "10100");
-    japanese.insert('シ', "11010");
-    japanese.insert('チ', "0010");
-    japanese.insert('ニ', "1010");
-    japanese.insert('ヒ', "11001");
-    japanese.insert('ミ', "00101");
-    japanese.insert('リ', "110");
-    japanese.insert('ヰ', "01001");
-    japanese.insert('ウ', "001");
-    japanese.insert('ク', "0001");
-    japanese.insert('ス', "11101");
-    japanese.insert('ツ', "0110");
-    japanese.insert('ヌ', "0000");
-    japanese.insert('フ', "1100");
-    japanese.insert('ム', "1");
-    japanese.insert('ユ', "10011");
-    japanese.insert('ル', "10110");
-    japanese.insert('ン', "01010");
-    japanese.insert('エ', "10111");
-    japanese.insert('ケ', "1011");
-    japanese.insert('セ', "01110");
-    japanese.insert('テ', "01011");
-    japanese.insert('ネ', "1101");
-    japanese.insert('ヘ', "0");
-    japanese.insert('メ', "10001");
-    japanese.insert('レ', "111");
-    japanese.insert('ヱ', "01100");
-    japanese.insert('オ', "01000");
-    japanese.insert('コ', "1111");
-    japanese.insert('ソ', "1110");
-    japanese.insert('ト', "00100");
-    japanese.insert('ノ', "0011");
-    japanese.insert('ホ', "100");
-    japanese.insert('モ', "10010");
-    japanese.insert('ヨ', "11");
-    japanese.insert('ロ', "0101");
-    japanese.insert('ヲ', "0111");
-    japanese.insert('゛', "00");
-    japanese.insert('゜', "00110");
-    japanese.insert('。', "010100");
-    japanese.insert('ー', "01101");
-    japanese.insert('、', "010101");
-    japanese.insert('（', "101101");
-    japanese.insert('）', "010010");
-    japanese
-}
+    /// Returns the mappings Wabun code is read with, or `None` if the Japanese character set is
+    /// not allowed.
+    fn wabun_set(&self) -> Option<SetRef<'_>> {
+        let set = MorseCharacterSet::Japanese;
+        let allowed = self.options.allowed.as_deref();
+        allowed
+            .is_none_or(|allowed| allowed.contains(&set))
+            .then(|| self.built_in_set(set))
+            .flatten()
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Korean characters.
-///
-/// This function provides the Morse code mappings for Korean Hangul characters, where each key is a Korean character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Korean characters as keys and their Morse code representations as values.
-fn korean_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut korean = BTreeMap::new();
-    korean.insert('ㄱ', "0100");
-    korean.insert('ㄴ', "0010");
-    korean.insert('ㄷ', "1000");
-    korean.insert('ㄹ', "0001");
-    korean.insert('ㅁ', "11");
-    korean.insert('ㅂ', "011");
-    korean.insert('ㅅ', "110");
-    korean.insert('ㅇ', "101");
-    korean.insert('ㅈ', "0110");
-    korean.insert('ㅊ', "1010");
-    korean.insert('ㅋ', "1001");
-    korean.insert('ㅌ', "1100");
-    korean.insert('ㅍ', "111");
-    korean.insert('ㅎ', "0111");
-    korean.insert('ㅏ', "0");
-    korean.insert('ㅑ', "00");
-    korean.insert('ㅓ', "1");
-    korean.insert('ㅕ', "000");
-    korean.insert('ㅗ', "01");
-    korean.insert('ㅛ', "10");
-    korean.insert('ㅜ', "0000");
-    korean.insert('ㅠ', "010");
-    korean.insert('ㅡ', "100");
-    korean.insert('ㅣ', "001");
-    korean
-}
+    /// Returns the text to encode in place of `c`, which has no code: its transliteration if
+    /// enabled and known, or else whatever the invalid character policy decides.
+    fn substitute(&self, c: char) -> Option<Replacement> {
+        self.options
+            .transliterate
+            .then(|| transliterate(c))
+            .flatten()
+            .map(|text| Replacement::Text(Cow::Borrowed(text)))
+            .or_else(|| self.options.invalid_char_policy.resolve(c))
+    }
 
-/// Returns a `BTreeMap` of Morse code representations for Thai characters.
-///
-/// This function provides the Morse code mappings for Thai script characters, where each key is a Thai character
-/// and each value is its Morse code representation.
-///
-/// # Returns
-/// A `BTreeMap` with Thai characters as keys and their Morse code representations as values.   
-fn thai_chars<'a>() -> BTreeMap<char, &'a str> {
-    let mut thai = BTreeMap::new();
-    thai.insert('ก', "110");
-    thai.insert('ข', "1010");
-    thai.insert('ค', "101");
-    thai.insert('ง', "10110");
-    thai.insert('จ', "10010");
-    thai.insert('ฉ', "1111");
-    thai.insert('ช', "1001");
-    thai.insert('ซ', "1100");
-    thai.insert('ญ', "0111");
-    thai.insert('ด', "100");
-    thai.insert('ต', "1");
-    thai.insert('ถ', "10100");
-    thai.insert('ท', "10011");
-    thai.insert('น', "10");
-    thai.insert('บ', "1000");
-    thai.insert('ป', "0110");
-    thai.insert('ผ', "1101");
-    thai.insert('ฝ', "10101");
-    thai.insert('พ', "01100");
-    thai.insert('ฟ', "0010");
-    thai.insert('ม', "11");
-    thai.insert('ย', "1011");
-    thai.insert('ร', "010");
-    thai.insert('ล', "0100");
-    thai.insert('ว', "011");
-    thai.insert('ส', "000");
-    thai.insert('ห', "0000");
-    thai.insert('อ', "10001");
-    thai.insert('ฮ', "11011");
-    thai.insert('ฤ', "01011");
-    thai.insert('ะ', "01000");
-    thai.insert('า', "01");
-    thai.insert('ิ', "00100");
-    thai.insert('ี', "00");
-    thai.insert('ึ', "00110");
-    thai.insert('ื', "0011");
-    thai.insert('ุ', "00101");
-    thai.insert('ู', "1110");
-    thai.insert('เ', "0");
-    thai.insert('แ', "0101");
-    thai.insert('ไ', "01001");
-    thai.insert('โ', "111");
-    thai.insert('ำ', "00010");
-    thai.insert('่', "001");
-    thai.insert('้', "0001");
-    thai.insert('๊', "11000");
-    thai.insert('๋', "01010");
-    thai.insert('ั', "01101");
-    thai.insert('็', "11100");
-    thai.insert('์', "11001");
-    thai.insert('ๆ', "10111");
-    thai.insert('ฯ', "11010");
-    thai
-}
+    /// Encodes `text` into `out`, calling `on_unsupported` for every character the invalid character policy could not encode
+    /// and `on_substituted` for every character encoded as a replacement, with its index and its replacement.
+    fn encode_with<W: fmt::Write>(
+        &self,
+        text: &str,
+        out: &mut W,
+        mut on_unsupported: impl FnMut(UnsupportedChar),
+        mut on_substituted: impl FnMut(usize, char, &Replacement),
+    ) -> fmt::Result {
+        let mut last_reported = None;
+        let mut is_first = true;
+        let mut word_gap = false;
 
-/// Retrieves a `Characters` map based on the given `Options` configuration.
-///
-/// This function generates a `Characters` map that includes Morse code representations for various character sets,
-/// incorporating any options specified, such as a priority character set or custom separators and spaces.
-///
-/// # Parameters
-/// - `options`: A configuration object containing options for character sets and Morse code representation.
-///
-/// # Returns
-/// A `Characters` map where each key is a `MorseCharacterSet` and each value is a `BTreeMap` of characters and their Morse code representations.
-fn get_characters(options: Options) -> Characters {
-    let base_characters = base_characters();
-    let mut characters = base_characters.clone();
+        for (index, original, unit) in self.units(text) {
+            if let Unit::Substituted(replacement) = &unit {
+                on_substituted(index, original, replacement);
+                continue;
+            }
+            // Gaps are only written between two units, so runs of whitespace and whitespace next
+            // to skipped characters collapse into a single word gap.
+            if unit == Unit::WordGap {
+                word_gap |= !is_first;
+                continue;
+            }
+            if !is_first {
+                out.write_str(&self.options.separator)?;
+            }
+            if core::mem::take(&mut word_gap) {
+                out.write_str(&self.options.space)?;
+                out.write_str(&self.options.separator)?;
+            }
+            is_first = false;
+            match unit {
+                Unit::Code(code) => {
+                    write!(
+                        out,
+                        "{}",
+                        code.display_with(&self.options.dot, &self.options.dash)
+                    )?;
+                }
+                Unit::WordGap | Unit::Substituted(_) => {}
+                Unit::Unsupported(c) => {
+                    out.write_char(c)?;
+                    if last_reported != Some(index) {
+                        last_reported = Some(index);
+                        on_unsupported(UnsupportedChar {
+                            character: original,
+                            index,
+                            script: Script::of(original),
+                        });
+                    }
+                }
+            }
+        }
 
-    if let Some(priority_set) = base_characters.get(&options.priority) {
-        characters.insert(MorseCharacterSet::Undefined, priority_set.clone());
+        Ok(())
     }
 
-    if let Some(set_1) = base_characters.get(&MorseCharacterSet::Latin) {
-        let mut new_set_1 = set_1.clone();
-        new_set_1.insert(options.separator, options.space.to_string());
-        characters.insert(MorseCharacterSet::Latin, new_set_1);
+    /// Returns a decoder for Morse code that arrives a piece at a time.
+    #[must_use]
+    pub fn stream_decoder(&self) -> StreamDecoder<'_> {
+        StreamDecoder::new(self)
     }
 
-    characters
-        .into_iter()
-        .map(|(key, value)| (key, value.into_iter().collect::<BTreeMap<char, String>>()))
-        .collect::<Characters>()
-}
-
-/// Returns a `Characters` map with Morse code characters mapped to custom symbols based on the given `Options` configuration.
-///
-/// This function generates a `Characters` map by replacing Morse code symbols (dots and dashes) with custom symbols
-/// specified in the `options` configuration.
-///
-/// # Parameters
-/// - `options`: A configuration object containing custom symbols for dots and dashes.
-///
-/// # Returns
-/// A `Characters` map where each key is a `MorseCharacterSet` and each value is a `BTreeMap` of characters and their updated Morse code representations.
-fn get_mapped_characters(options: Options) -> Characters {
-    let mut mapped = BTreeMap::new();
-    let characters = get_characters(options);
-
-    for (set, chars) in &characters {
-        let mut new_set = BTreeMap::new();
-        for (key, value) in chars {
-            let mapped_value = value
-                .replace('0', &options.dot.to_string())
-                .replace('1', &options.dash.to_string());
-            new_set.insert(*key, mapped_value);
-        }
-        mapped.insert(*set, new_set);
+    /// Returns the dichotomic tree of this `MorseCode`, for decoding one dot or dash at a time.
+    #[must_use]
+    pub fn tree(&self) -> MorseTree<'_> {
+        MorseTree::new(self)
     }
 
-    mapped
-}
-
-/// Returns a `BTreeMap` of Morse code representations swapped with their character mappings.
-///
-/// This function generates a mapping where Morse code representations are keys and the corresponding characters are values.
-/// This is useful for reverse lookup of Morse code representations.
-///
-/// # Parameters
-/// - `options`: A configuration object containing custom symbols for dots and dashes.
-///
-/// # Returns
-/// A `BTreeMap` where each key is a Morse code representation and each value is the corresponding character.
-fn swap_characters(options: Options) -> BTreeMap<String, char> {
-    let mut swapped = BTreeMap::new();
-    let mapped_characters = get_mapped_characters(options);
-
-    for chars in mapped_characters.into_values() {
-        for (key, value) in chars {
-            swapped.entry(value).or_insert(key);
+    /// Returns the sequence the given character is encoded as, or `None` if it has no code.
+    ///
+    /// Characters are matched as [`MorseCode::encode`] matches them, after converting them to upper case.
+    #[must_use]
+    pub fn code(&self, character: char) -> Option<MorseSequence> {
+        let mut upper = character.to_uppercase();
+        match (upper.next(), upper.next()) {
+            (Some(upper), None) => self.lookup(upper),
+            _ => None,
         }
     }
 
-    swapped
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Returns the character the given sequence decodes to, or `None` if it matches no character.
+    ///
+    /// When several characters share the sequence, the one from the first character set in lookup
+    /// order is returned: the priority set, then the fallback sets, then the others.
+    #[must_use]
+    pub fn character(&self, sequence: MorseSequence) -> Option<char> {
+        self.sets().find_map(|set| set.character(sequence))
+    }
 
-    #[test]
-    fn encodes_english_alphabet() {
-        assert_eq!(MorseCode::default().encode("the quick brown fox jumps over the lazy dog"), "- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --.");
-        assert_eq!(MorseCode::new(Options { dash: '–', dot: '•', space: '\\', ..Default::default() }).encode("the quick brown fox jumps over the lazy dog"), "– •••• • \\ ––•– ••– •• –•–• –•– \\ –••• •–• ––– •–– –• \\ ••–• ––– –••– \\ •––– ••– –– •––• ••• \\ ––– •••– • •–• \\ – •••• • \\ •–•• •– ––•• –•–– \\ –•• ––– ––•");
+    /// Returns the code of the given character, looking through the character sets in priority order.
+    fn lookup(&self, character: char) -> Option<MorseSequence> {
+        self.sets().find_map(|set| set.code(character))
     }
 
-    #[test]
-    fn decodes_english_alphabet() {
-        assert_eq!(MorseCode::default().decode("- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --."), "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG");
-        assert_eq!(MorseCode::new(Options {dash: '–', dot: '•', space: '\\',..Default::default()}).decode("– •••• • \\ ––•– ••– •• –•–• –•– \\ –••• •–• ––– •–– –• \\ ••–• ––– –••– \\ •––– ••– –– •––• ••• \\ ––– •••– • •–• \\ – •••• • \\ •–•• •– ––•• –•–– \\ –•• ––– ––•"), "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG");
+    /// Returns the character sets in lookup order: custom sets first, then the priority set, then
+    /// the fallback sets, then every other built-in set in declaration order, replaced ones taking
+    /// the place of their table. Built-in sets that are not allowed are left out.
+    pub(crate) fn sets(&self) -> impl Iterator<Item = SetRef<'_>> {
+        let built_in = tables::lookup_order(
+            self.options.priority,
+            &self.options.fallback,
+            self.options.allowed.as_deref(),
+        )
+        .filter_map(|set| self.built_in_set(set));
+        self.custom.iter().map(SetRef::Custom).chain(built_in)
     }
 
-    #[test]
-    fn decodes_numbers() {
-        assert_eq!(
-            MorseCode::default()
-                .decode("----- .---- ..--- ...-- ....- ..... -.... --... ---.. ----."),
-            "0123456789"
-        );
+    /// Returns the mappings of a built-in character set: its replacement if it has one, or else
+    /// its table.
+    fn built_in_set(&self, set: MorseCharacterSet) -> Option<SetRef<'_>> {
+        match self.replaced.get(&set) {
+            Some(characters) => Some(SetRef::Custom(characters)),
+            None => tables::table(set).map(SetRef::Table),
+        }
     }
 
-    #[test]
-    fn encodes_punctuation() {
-        let morse_code = MorseCode::default();
-        assert_eq!(
-            morse_code.encode(".,?'!/("),
+    /// Decodes the given Morse code string into text using the struct’s options.
+    ///
+    /// # Arguments
+    ///
+    /// * `morse` - The Morse code string to decode.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the decoded text.
+    pub fn decode(&self, morse: &str) -> String {
+        let mut result = String::with_capacity(morse.len() / 2);
+        self.decode_with(morse, &mut result, |_, _| {})
+            .expect("writing to a `String` cannot fail");
+        result
+    }
+
+    /// Decodes the given Morse code string into text, writing the result straight to `out`.
+    ///
+    /// Apart from what a custom [`UnknownSequencePolicy`] may allocate, this method performs no heap
+    /// allocation, which makes it suitable for constrained targets.
+    ///
+    /// # Arguments
+    ///
+    /// * `morse` - The Morse code string to decode.
+    /// * `out` - The sink the decoded text is written to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn decode_to<W: fmt::Write>(&self, morse: &str, out: &mut W) -> fmt::Result {
+        self.decode_with(morse, out, |_, _| {})
+    }
+
+    /// Decodes the given Morse code string into text, failing on any token that is not a known code.
+    ///
+    /// Tokens resolved by the configured [`UnknownSequencePolicy`] are not considered failures.
+    ///
+    /// # Arguments
+    ///
+    /// * `morse` - The Morse code string to decode.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the decoded text.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] listing every unrecognized token, together with its byte range in
+    /// `morse` and the characters whose code it almost matched.
+    pub fn try_decode(&self, morse: &str) -> Result<String, DecodeError> {
+        let mut result = String::with_capacity(morse.len() / 2);
+        let mut unrecognized = Vec::new();
+        self.decode_with(morse, &mut result, |range, token| {
+            unrecognized.push((range, token));
+        })
+        .expect("writing to a `String` cannot fail");
+
+        if unrecognized.is_empty() {
+            return Ok(result);
+        }
+
+        let unrecognized = unrecognized
+            .into_iter()
+            .map(|(range, token)| UnrecognizedToken {
+                token: token.to_string(),
+                range,
+                candidates: self.tree().near_misses(token),
+            })
+            .collect();
+
+        Err(DecodeError::new(unrecognized))
+    }
+
+    /// Decodes `morse` into `out`, calling `on_unrecognized` for every token the unknown sequence policy rejected.
+    fn decode_with<'a, W: fmt::Write>(
+        &self,
+        morse: &'a str,
+        out: &mut W,
+        mut on_unrecognized: impl FnMut(Range<usize>, &'a str),
+    ) -> fmt::Result {
+        let mut state = DecodeState::new();
+        for piece in self.pieces(morse) {
+            match piece {
+                Piece::Token { range, sequence } => {
+                    let token = &morse[range.clone()];
+                    if !self.decode_token(token, sequence, &mut state, out)? {
+                        on_unrecognized(range, token);
+                    }
+                }
+                Piece::WordGap => state.write_str(" ", out)?,
+            }
+        }
+
+        state.flush(out)
+    }
+
+    /// Decodes the given Morse code string with every alphabet whose letters are told apart by
+    /// frequency, ranking the results by how plausible they are.
+    ///
+    /// Each decoding looks letters up in a single alphabet, then in the numbers and punctuation
+    /// sets, ignoring the priority and fallback options. Custom character sets are looked up first
+    /// as usual. Tokens that match no character are kept unchanged and weigh against the alphabet.
+    ///
+    /// # Arguments
+    ///
+    /// * `morse` - The Morse code string to decode.
+    ///
+    /// # Returns
+    ///
+    /// An [`Interpretation`] for every alphabet, from the most plausible to the least plausible.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCharacterSet, MorseCode};
+    ///
+    /// let morse_code = MorseCode::default();
+    /// let morse = morse_code.encode("ВСЕ СПОКОЙНО ЖДЕМ ВАШЕГО ОТВЕТА");
+    /// let interpretations = morse_code.decode_auto(&morse);
+    ///
+    /// assert_eq!(interpretations[0].set, MorseCharacterSet::Cyrillic);
+    /// assert_eq!(interpretations[0].text, "ВСЕ СПОКОЙНО ЖДЕМ ВАШЕГО ОТВЕТА");
+    /// assert_eq!(interpretations[1].text, "WSE SPOKOJNO VDEM WA----EGO OTWETA");
+    /// assert!(interpretations[0].score > interpretations[1].score);
+    /// ```
+    #[must_use]
+    pub fn decode_auto(&self, morse: &str) -> Vec<Interpretation> {
+        let mut interpretations = detect::MODELS
+            .iter()
+            .map(|model| {
+                let sets = [
+                    model.set,
+                    MorseCharacterSet::Numbers,
+                    MorseCharacterSet::Punctuation,
+                ];
+                let mut text = String::with_capacity(morse.len() / 2);
+                let mut state = DecodeState::new();
+                let (mut cost, mut count) = (0_u64, 0_u64);
+                for piece in self.pieces(morse) {
+                    let Piece::Token { range, sequence } = piece else {
+                        state
+                            .write_str(" ", &mut text)
+                            .expect("writing to a `String` cannot fail");
+                        continue;
+                    };
+                    // Of the letters sharing a code, the one the model knows is read.
+                    let character = sequence.and_then(|sequence| {
+                        self.custom
+                            .iter()
+                            .map(SetRef::Custom)
+                            .find_map(|set| set.character(sequence))
+                            .or_else(|| {
+                                let set = self.built_in_set(model.set)?;
+                                model.letter(set, sequence)
+                            })
+                            .or_else(|| {
+                                sets.into_iter()
+                                    .filter_map(|set| self.built_in_set(set))
+                                    .find_map(|set| set.character(sequence))
+                            })
+                    });
+                    match character {
+                        Some(c) => {
+                            if c.is_alphabetic() {
+                                cost += u64::from(model.cost(c));
+                                count += 1;
+                            }
+                            state.write_char(c, &mut text)
+                        }
+                        None => {
+                            cost += u64::from(model.unrecognized_cost());
+                            count += 1;
+                            state.write_str(&morse[range], &mut text)
+                        }
+                    }
+                    .expect("writing to a `String` cannot fail");
+                }
+                state
+                    .flush(&mut text)
+                    .expect("writing to a `String` cannot fail");
+                Interpretation {
+                    set: model.set,
+                    text,
+                    score: detect::Model::score(cost, count),
+                }
+            })
+            .collect::<Vec<_>>();
+        interpretations.sort_by(|a, b| b.score.total_cmp(&a.score));
+        interpretations
+    }
+
+    /// Spells Korean text in SKATS letters, the Latin letters sharing the Morse code of each jamo.
+    ///
+    /// Hangul syllables and compound jamo are first broken into the basic jamo they are sent as.
+    /// Characters without a Korean code are kept unchanged. The result encodes to the same Morse
+    /// code as the Korean text, which makes it a readable intermediate form;
+    /// [`MorseCode::from_skats`] converts it back.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The Korean text to spell.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the SKATS letters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCharacterSet, MorseCode, Options};
+    ///
+    /// let morse_code = MorseCode::new(Options {
+    ///     priority: MorseCharacterSet::Korean,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(morse_code.to_skats("한국 1"), "JEFLHL 1");
+    /// assert_eq!(morse_code.encode("JEFLHL"), morse_code.encode("한국"));
+    /// ```
+    #[must_use]
+    pub fn to_skats(&self, text: &str) -> String {
+        text.chars()
+            .flat_map(|c| {
+                let jamo = hangul::decompose(c);
+                let plain = jamo.is_none().then_some(c);
+                plain.into_iter().chain(jamo.into_iter().flatten())
+            })
+            .map(|c| {
+                self.transcribe(c, MorseCharacterSet::Korean, MorseCharacterSet::Latin)
+                    .unwrap_or(c)
+            })
+            .collect()
+    }
+
+    /// Reads SKATS letters back as Korean text, recomposing the jamo into Hangul syllables.
+    ///
+    /// Letters are accepted in either case. Characters that are not SKATS letters, such as spaces
+    /// and digits, are kept unchanged and end the syllable being composed.
+    ///
+    /// SKATS spells a double consonant as two single ones, so two identical consonants before a
+    /// vowel are read as closing one syllable and opening the next: `학교` comes back unchanged,
+    /// but `아까` comes back as `악가`. [`MorseCode::decode`] recomposes syllables the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `letters` - The SKATS letters to read.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the Korean text.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCharacterSet, MorseCode, Options};
+    ///
+    /// let morse_code = MorseCode::new(Options {
+    ///     priority: MorseCharacterSet::Korean,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(morse_code.from_skats("jeflhl 1"), "한국 1");
+    ///
+    /// // Decoding with the Latin alphabet shows the SKATS letters of Korean Morse code.
+    /// let latin = MorseCode::default();
+    /// let letters = latin.decode(&morse_code.encode("한국"));
+    /// assert_eq!(letters, "JEFLHL");
+    /// assert_eq!(morse_code.from_skats(&letters), "한국");
+    /// ```
+    #[must_use]
+    pub fn from_skats(&self, letters: &str) -> String {
+        let mut text = String::with_capacity(letters.len());
+        let mut composer = HangulComposer::new();
+        for c in letters.chars() {
+            let jamo = c.to_uppercase().next().and_then(|upper| {
+                self.transcribe(upper, MorseCharacterSet::Latin, MorseCharacterSet::Korean)
+            });
+            match jamo {
+                Some(jamo) => composer.write_char(jamo, &mut text),
+                None => composer.flush(&mut text).map(|()| text.push(c)),
+            }
+            .expect("writing to a `String` cannot fail");
+        }
+        composer
+            .flush(&mut text)
+            .expect("writing to a `String` cannot fail");
+        text
+    }
+
+    /// Returns the character of the set `to` sharing the code of `c` in the set `from`, if any.
+    fn transcribe(&self, c: char, from: MorseCharacterSet, to: MorseCharacterSet) -> Option<char> {
+        let code = self.built_in_set(from)?.code(c)?;
+        self.built_in_set(to)?.character(code)
+    }
+
+    /// Decodes a single token into `out`, returning `false` if the unknown sequence policy rejected it.
+    ///
+    /// `sequence` holds the dots and dashes of the token, or `None` if it contains anything else.
+    /// `state` carries what the previous tokens left for this one.
+    pub(crate) fn decode_token<W: fmt::Write>(
+        &self,
+        token: &str,
+        sequence: Option<MorseSequence>,
+        state: &mut DecodeState,
+        out: &mut W,
+    ) -> Result<bool, fmt::Error> {
+        if self.options.wabun_switching {
+            match sequence {
+                Some(tables::WABUN_START) => {
+                    state.wabun = true;
+                    return Ok(true);
+                }
+                Some(tables::WABUN_END) => {
+                    state.wabun = false;
+                    return Ok(true);
+                }
+                _ => {}
+            }
+        }
+
+        let japanese = state.wabun.then(|| self.wabun_set()).flatten();
+        let character = sequence.and_then(|sequence| {
+            self.custom
+                .iter()
+                .map(SetRef::Custom)
+                .chain(japanese)
+                .find_map(|set| set.character(sequence))
+                .or_else(|| self.character(sequence))
+        });
+        if let Some(c) = character {
+            state.write_char(c, out)?;
+        } else if let Some(replacement) = self.options.unknown_sequence_policy.resolve(token) {
+            state.flush(out)?;
+            write!(out, "{replacement}")?;
+        } else {
+            state.write_str(token, out)?;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Splits a Morse code string into its tokens and word gaps.
+    ///
+    /// Tokens are delimited by the configured separator, by the configured space or by any
+    /// whitespace that is not part of a configured symbol. A run of spaces, with or without
+    /// separators around them, makes a single word gap.
+    fn pieces<'a>(&'a self, morse: &'a str) -> impl Iterator<Item = Piece> + 'a {
+        let mut symbols = symbol::symbols(&self.options, morse).peekable();
+
+        core::iter::from_fn(move || loop {
+            let (mut symbol, range) = symbols.next()?;
+            match symbol {
+                Symbol::Space => {
+                    while symbols.next_if(|(symbol, _)| !symbol.is_token()).is_some() {}
+                    return Some(Piece::WordGap);
+                }
+                Symbol::Separator | Symbol::Whitespace => continue,
+                Symbol::Dot | Symbol::Dash | Symbol::Other => {}
+            }
+
+            let (start, mut end) = (range.start, range.end);
+            let mut sequence = Some(MorseSequence::EMPTY);
+            loop {
+                sequence = sequence.and_then(|sequence| match symbol.element()? {
+                    Element::Dot => sequence.dot(),
+                    _ => sequence.dash(),
+                });
+                match symbols.next_if(|(symbol, _)| symbol.is_token()) {
+                    Some((next, range)) => (symbol, end) = (next, range.end),
+                    None => break,
+                }
+            }
+            return Some(Piece::Token {
+                range: start..end,
+                sequence,
+            });
+        })
+    }
+}
+
+/// What decoding a token depends on besides the token itself.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DecodeState {
+    /// Whether tokens are in Wabun mode, as switched by prosigns.
+    wabun: bool,
+    /// Recomposes decoded kana with their dakuten or handakuten.
+    kana: KanaComposer,
+    /// Recomposes decoded jamo into Hangul syllables.
+    hangul: HangulComposer,
+}
+
+impl DecodeState {
+    /// Creates the state at the start of a message.
+    pub(crate) const fn new() -> Self {
+        Self {
+            wabun: false,
+            kana: KanaComposer::new(),
+            hangul: HangulComposer::new(),
+        }
+    }
+
+    /// Writes the decoded character `c` to `out`, or holds it back until the characters that may
+    /// compose with it are known.
+    pub(crate) fn write_char<W: fmt::Write>(&mut self, c: char, out: &mut W) -> fmt::Result {
+        if hangul::is_jamo(c) {
+            self.kana.flush(out)?;
+            self.hangul.write_char(c, out)
+        } else {
+            self.hangul.flush(out)?;
+            self.kana.write_char(c, out)
+        }
+    }
+
+    /// Writes `text` to `out` after whatever was held back.
+    pub(crate) fn write_str<W: fmt::Write>(&mut self, text: &str, out: &mut W) -> fmt::Result {
+        self.flush(out)?;
+        out.write_str(text)
+    }
+
+    /// Writes whatever was held back to `out`.
+    pub(crate) fn flush<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        self.kana.flush(out)?;
+        self.hangul.flush(out)
+    }
+
+    /// Returns `true` if decoded text is held back.
+    pub(crate) const fn is_pending(&self) -> bool {
+        self.kana.is_pending() || self.hangul.is_pending()
+    }
+}
+
+/// A piece of Morse code text: a token, or a gap between words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Piece {
+    /// A token, with its byte range in the text and its dots and dashes, if it holds nothing else.
+    Token {
+        range: Range<usize>,
+        sequence: Option<MorseSequence>,
+    },
+    /// The gap between two words.
+    WordGap,
+}
+
+/// A unit of encoded text: the code of one character, or a gap between words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Unit {
+    /// The code of a character.
+    Code(MorseSequence),
+    /// A character without a Morse code representation, to be emitted unchanged.
+    Unsupported(char),
+    /// The gap between two words.
+    WordGap,
+    /// A marker preceding the units of the replacement of a character without a code.
+    Substituted(Replacement),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::sync::Arc;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn encodes_english_alphabet() {
+        assert_eq!(MorseCode::default().encode("the quick brown fox jumps over the lazy dog"), "- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --.");
+        assert_eq!(MorseCode::new(Options { dash: "–".into(), dot: "•".into(), space: "\\".into(), ..Default::default() }).encode("the quick brown fox jumps over the lazy dog"), "– •••• • \\ ––•– ••– •• –•–• –•– \\ –••• •–• ––– •–– –• \\ ••–• ––– –••– \\ •––– ••– –– •––• ••• \\ ––– •••– • •–• \\ – •••• • \\ •–•• •– ––•• –•–– \\ –•• ––– ––•");
+    }
+
+    #[test]
+    fn decodes_english_alphabet() {
+        assert_eq!(MorseCode::default().decode("- .... . / --.- ..- .. -.-. -.- / -... .-. --- .-- -. / ..-. --- -..- / .--- ..- -- .--. ... / --- ...- . .-. / - .... . / .-.. .- --.. -.-- / -.. --- --."), "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG");
+        assert_eq!(MorseCode::new(Options {dash: "–".into(), dot: "•".into(), space: "\\".into(),..Default::default()}).decode("– •••• • \\ ––•– ••– •• –•–• –•– \\ –••• •–• ––– •–– –• \\ ••–• ––– –••– \\ •––– ••– –– •––• ••• \\ ––– •••– • •–• \\ – •••• • \\ •–•• •– ––•• –•–– \\ –•• ––– ––•"), "THE QUICK BROWN FOX JUMPS OVER THE LAZY DOG");
+    }
+
+    #[test]
+    fn decodes_numbers() {
+        assert_eq!(
+            MorseCode::default()
+                .decode("----- .---- ..--- ...-- ....- ..... -.... --... ---.. ----."),
+            "0123456789"
+        );
+    }
+
+    #[test]
+    fn encodes_punctuation() {
+        let morse_code = MorseCode::default();
+        assert_eq!(
+            morse_code.encode(".,?'!/("),
             ".-.-.- --..-- ..--.. .----. -.-.-- -..-. -.--."
         );
         assert_eq!(
@@ -1013,4 +1457,645 @@ mod tests {
         );
         assert_eq!(morse_code.encode("ÙŬŽŹŻ"), "..-- ..-- --..- --..-. --..-");
     }
+
+    #[test]
+    fn try_decode_reports_unrecognized_tokens() {
+        let morse_code = MorseCode::default();
+        assert_eq!(morse_code.try_decode(".... .."), Ok("HI".to_string()));
+
+        let error = morse_code.try_decode(".... ..-.-- / .-x").unwrap_err();
+        let tokens = error.tokens();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].token, "..-.--");
+        assert_eq!(tokens[0].range, 5..11);
+        assert_eq!(tokens[0].candidates, ['!', '2', '3', '¿', 'テ']);
+        assert_eq!(tokens[1].token, ".-x");
+        assert_eq!(tokens[1].range, 14..17);
+        assert_eq!(tokens[1].candidates, ['A', 'R', 'W']);
+    }
+
+    #[test]
+    fn try_encode_reports_unsupported_characters() {
+        let morse_code = MorseCode::default();
+        assert_eq!(morse_code.try_encode("Hi"), Ok(".... ..".to_string()));
+
+        let error = morse_code.try_encode(" a%b€").unwrap_err();
+        assert_eq!(
+            error.chars(),
+            [
+                UnsupportedChar {
+                    character: '%',
+                    index: 2,
+                    script: Script::Common,
+                },
+                UnsupportedChar {
+                    character: '€',
+                    index: 4,
+                    script: Script::Common,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_invalid_char_policy() {
+        let with_policy = |invalid_char_policy| {
+            MorseCode::new(Options {
+                invalid_char_policy,
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(MorseCode::default().encode("a%b"), ".- % -...");
+        assert_eq!(
+            with_policy(InvalidCharPolicy::Skip).encode("a%b"),
+            ".- -..."
+        );
+        assert_eq!(
+            with_policy(InvalidCharPolicy::Replace('?')).encode("a%b"),
+            ".- ..--.. -..."
+        );
+        assert_eq!(
+            with_policy(InvalidCharPolicy::Transliterate).try_encode("ĖŒ”"),
+            Ok(". --- . .-..-.".to_string())
+        );
+        assert!(with_policy(InvalidCharPolicy::Transliterate)
+            .try_encode("€")
+            .is_err());
+
+        let rejected = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&rejected);
+        let morse_code = with_policy(InvalidCharPolicy::custom(move |c| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            (c == '%').then(|| "0/0".to_string())
+        }));
+        assert_eq!(morse_code.encode("%€"), "----- -..-. ----- €");
+        assert_eq!(rejected.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn transliterates_unsupported_characters() {
+        let morse_code = MorseCode::new(Options {
+            transliterate: true,
+            invalid_char_policy: InvalidCharPolicy::Skip,
+            ..Default::default()
+        });
+        assert_eq!(morse_code.encode("ő€—"), "--- -....-");
+
+        let morse_code = MorseCode::new(Options {
+            transliterate: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            morse_code.try_encode("ĖŒ”"),
+            Ok(". --- . .-..-.".to_string())
+        );
+        assert!(morse_code.try_encode("€").is_err());
+
+        let mut latin_only = MorseCode::new(Options {
+            transliterate: true,
+            ..Default::default()
+        });
+        latin_only.replace_character_set(MorseCharacterSet::Cyrillic, CharacterSet::new());
+        let (encoded, substitutions) = latin_only.encode_with_substitutions("Жёлтый €");
+        assert_eq!(encoded, "--.. .... . .-.. - -.-- .. / €");
+        assert_eq!(
+            substitutions
+                .iter()
+                .map(|s| (s.index, s.replacement.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, "ZH"), (1, "E"), (2, "L"), (3, "T"), (4, "Y"), (5, "I")]
+        );
+        assert_eq!(substitutions[1].character, 'ё');
+
+        let (_, substitutions) = MorseCode::new(Options {
+            invalid_char_policy: InvalidCharPolicy::Skip,
+            ..Default::default()
+        })
+        .encode_with_substitutions("a%");
+        assert_eq!(
+            substitutions,
+            [Substitution {
+                character: '%',
+                index: 1,
+                replacement: String::new()
+            }]
+        );
+    }
+
+    #[test]
+    fn normalizes_input() {
+        let morse_code = MorseCode::default();
+        assert_eq!(morse_code.encode("E\u{301}"), morse_code.encode("É"));
+        assert_eq!(morse_code.encode("ＳＯＳ"), "... --- ...");
+        assert_eq!(
+            morse_code.try_encode("n\u{303}%").unwrap_err().chars()[0].index,
+            2
+        );
+
+        let transliterating = MorseCode::new(Options {
+            transliterate: true,
+            ..Default::default()
+        });
+        for text in ["Ậ", "A\u{323}\u{302}", "A\u{302}\u{323}"] {
+            assert_eq!(transliterating.try_encode(text).as_deref(), Ok(".-"));
+        }
+        let japanese = MorseCode::new(Options {
+            priority: MorseCharacterSet::Japanese,
+            ..Default::default()
+        });
+        assert_eq!(japanese.try_encode("ｶﾞ"), japanese.try_encode("ガ"));
+        assert!(japanese.try_encode("ｶﾞ").is_ok());
+    }
+
+    #[test]
+    fn applies_unknown_sequence_policy() {
+        let with_policy = |unknown_sequence_policy| {
+            MorseCode::new(Options {
+                unknown_sequence_policy,
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(
+            MorseCode::default().decode(".- ........ -..."),
+            "A........B"
+        );
+        assert_eq!(
+            with_policy(UnknownSequencePolicy::Skip).decode(".- ........ -..."),
+            "AB"
+        );
+        assert_eq!(
+            with_policy(UnknownSequencePolicy::Placeholder('�')).try_decode(".- ........ -..."),
+            Ok("A�B".to_string())
+        );
+
+        let morse_code = with_policy(UnknownSequencePolicy::custom(|sequence: &str| {
+            (sequence.len() == 8).then(|| "<HH>".to_string())
+        }));
+        assert_eq!(morse_code.decode(".- ........ -... ..--..--"), "A<HH>B<HH>");
+        let error = morse_code.try_decode(".- ......... -...").unwrap_err();
+        assert_eq!(error.tokens()[0].token, ".........");
+    }
+
+    #[test]
+    fn encodes_and_decodes_into_writer() {
+        /// A fixed-capacity sink, standing in for a buffer on a target without a heap.
+        struct Buffer {
+            bytes: [u8; 32],
+            len: usize,
+        }
+
+        impl fmt::Write for Buffer {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                let end = self.len + s.len();
+                self.bytes
+                    .get_mut(self.len..end)
+                    .ok_or(fmt::Error)?
+                    .copy_from_slice(s.as_bytes());
+                self.len = end;
+                Ok(())
+            }
+        }
+
+        let morse_code = MorseCode::default();
+        let mut buffer = Buffer {
+            bytes: [0; 32],
+            len: 0,
+        };
+        morse_code.encode_to("sos sos", &mut buffer).unwrap();
+        assert_eq!(&buffer.bytes[..buffer.len], b"... --- ... / ... --- ...");
+
+        buffer.len = 0;
+        morse_code
+            .decode_to("... --- ... / ... --- ...", &mut buffer)
+            .unwrap();
+        assert_eq!(&buffer.bytes[..buffer.len], b"SOS SOS");
+
+        buffer.len = 0;
+        assert_eq!(
+            morse_code.encode_to("sos sos sos", &mut buffer),
+            Err(fmt::Error)
+        );
+    }
+
+    #[test]
+    fn streams_elements() {
+        let morse_code = MorseCode::new(Options {
+            invalid_char_policy: InvalidCharPolicy::Replace('E'),
+            ..Default::default()
+        });
+        let elements = morse_code.elements(" n  %€ ").collect::<Vec<_>>();
+        assert_eq!(
+            elements,
+            [
+                (1, Element::Dash),
+                (1, Element::IntraCharGap),
+                (1, Element::Dot),
+                (2, Element::WordGap),
+                (4, Element::Dot),
+                (5, Element::CharGap),
+                (5, Element::Dot),
+            ]
+        );
+        assert_eq!(morse_code.elements("  ").next(), None);
+    }
+
+    #[test]
+    fn looks_up_sequences() {
+        let morse_code = MorseCode::default();
+        assert_eq!(morse_code.code('q'), Some(MorseSequence::from_code("--.-")));
+        assert_eq!(morse_code.code('%'), None);
+        assert_eq!(
+            morse_code.character(MorseSequence::from_code("--.-")),
+            Some('Q')
+        );
+        assert_eq!(morse_code.character(MorseSequence::EMPTY), None);
+
+        // Invalid characters are never mistaken for the digits the tables used to be written with.
+        let morse_code = MorseCode::new(Options {
+            space: "1".into(),
+            ..Default::default()
+        });
+        assert_eq!(morse_code.encode("e e"), ". 1 .");
+    }
+
+    #[test]
+    fn applies_custom_character_sets() {
+        let code = MorseSequence::from_code;
+        let mut morse_code = MorseCode::default();
+
+        let latin = morse_code.character_set_mut(MorseCharacterSet::Latin);
+        assert_eq!(
+            latin.insert('q', code(".-")),
+            Err(MappingError::CodeInUse {
+                character: 'Q',
+                code: code(".-"),
+                existing: 'A'
+            })
+        );
+        assert_eq!(
+            latin.insert('Q', MorseSequence::EMPTY),
+            Err(MappingError::EmptyCode { character: 'Q' })
+        );
+        assert_eq!(latin.insert('q', code("--.--.")), Ok(Some(code("--.-"))));
+        assert_eq!(latin.remove('W'), Some(code(".--")));
+        assert_eq!(morse_code.encode("qw"), "--.--. W");
+        assert_eq!(morse_code.decode("--.--. --.- .--"), "QЩВ");
+
+        let mut private = CharacterSet::new();
+        private.insert('Ω', code("........-")).unwrap();
+        private.insert('E', code("..-.-.-")).unwrap();
+        morse_code.add_character_set(private);
+        assert_eq!(morse_code.encode("eΩ"), "..-.-.- ........-");
+        assert_eq!(morse_code.decode("........- . ..-.-.-"), "ΩEE");
+
+        let e = morse_code.tree().root().dot().unwrap();
+        assert!(e.reachable().any(|c| c == 'Ω'));
+
+        assert_eq!(
+            CharacterSet::try_from_iter([('X', code("-.-.-")), ('Y', code("-.-.-"))]),
+            Err(MappingError::CodeInUse {
+                character: 'Y',
+                code: code("-.-.-"),
+                existing: 'X'
+            })
+        );
+        let set = CharacterSet::try_from_iter([('x', code("-.-.-")), ('Y', code("-.-.--"))]);
+        assert_eq!(set.map(|set| set.character(code("-.-.-"))), Ok(Some('X')));
+    }
+
+    #[test]
+    fn looks_up_sets_in_listed_order() {
+        let code = MorseSequence::from_code;
+        let morse_code = MorseCode::new(Options {
+            priority: MorseCharacterSet::Numbers,
+            fallback: [MorseCharacterSet::Greek, MorseCharacterSet::Cyrillic]
+                .as_slice()
+                .into(),
+            ..Default::default()
+        });
+        assert_eq!(morse_code.character(code(".-")), Some('Α'));
+        assert_eq!(morse_code.character(code(".-.-")), Some('Я'));
+        assert_eq!(morse_code.character(code(".-.-.-")), Some('.'));
+
+        let russian = MorseCode::new(
+            Options::builder()
+                .priority(MorseCharacterSet::Cyrillic)
+                .fallback(&[MorseCharacterSet::Numbers, MorseCharacterSet::Punctuation])
+                .allowed(&[
+                    MorseCharacterSet::Cyrillic,
+                    MorseCharacterSet::Numbers,
+                    MorseCharacterSet::Punctuation,
+                ])
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(MorseCode::default().decode("--.-."), "Ĝ");
+        assert_eq!(russian.decode("-.-- .- / .---- --.-."), "ЫА 1--.-.");
+        assert_eq!(russian.code('Q'), None);
+    }
+
+    #[test]
+    fn ranks_automatic_decodings() {
+        let morse_code = MorseCode::default();
+        for (text, set) in [
+            (
+                "THE WEATHER IS FINE AND WE EXPECT TO ARRIVE BEFORE NOON",
+                MorseCharacterSet::Latin,
+            ),
+            ("ΚΑΛΗΜΕΡΑ ΣΑΣ ΤΙ ΚΑΝΕΤΕ ΣΗΜΕΡΑ", MorseCharacterSet::Greek),
+            ("שלום עליכם מה שלומך היום", MorseCharacterSet::Hebrew),
+            (
+                "ВСЕ СЧАСТЛИВЫЕ СЕМЬИ ПОХОЖИ ДРУГ НА ДРУГА",
+                MorseCharacterSet::Cyrillic,
+            ),
+            (
+                "ПРИВЕТ КАК ДЕЛА У ТЕБЯ СЕГОДНЯ",
+                MorseCharacterSet::Cyrillic,
+            ),
+            ("МЫ ПРИЕДЕМ ЗАВТРА ВЕЧЕРОМ", MorseCharacterSet::Cyrillic),
+        ] {
+            let interpretations = morse_code.decode_auto(&morse_code.encode(text));
+            assert_eq!(interpretations.len(), 9);
+            assert_eq!(
+                (interpretations[0].set, &*interpretations[0].text),
+                (set, text)
+            );
+            assert!(interpretations
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score && pair[1].score <= 0.0));
+        }
+
+        let interpretations = morse_code.decode_auto("..... / .-.-.-");
+        assert!(interpretations.iter().all(|i| i.score == 0.0));
+        assert_eq!(interpretations[0].set, MorseCharacterSet::Latin);
+        assert_eq!(interpretations[0].text, "5 .");
+
+        for (text, set) in [
+            ("ゲンキデス", MorseCharacterSet::Japanese),
+            ("안녕 하세요", MorseCharacterSet::Korean),
+        ] {
+            let morse_code = MorseCode::new(Options {
+                priority: set,
+                ..Default::default()
+            });
+            let morse = morse_code.encode(text);
+            let interpretations = morse_code.decode_auto(&morse);
+            let interpretation = interpretations.iter().find(|i| i.set == set).unwrap();
+            assert_eq!(interpretation.text, text);
+            assert_eq!(morse_code.decode(&morse), text);
+        }
+    }
+
+    #[test]
+    fn switches_to_wabun_with_prosigns() {
+        let morse_code = MorseCode::new(Options {
+            wabun_switching: true,
+            ..Default::default()
+        });
+        let encoded = morse_code.encode("QSL ヨロシク 73");
+        assert_eq!(
+            encoded,
+            "--.- ... .-.. / -..--- -- .-.- --.-. ...- / ...-. --... ...--"
+        );
+        assert_eq!(morse_code.decode(&encoded), "QSL ヨロシク 73");
+        assert_eq!(morse_code.decode("--.- -..--- --.-"), "Qネ");
+        assert_eq!(MorseCode::default().decode("-..--- --.-"), "-..---Q");
+        assert_eq!(
+            morse_code.try_encode("Ŝ").unwrap_err().chars()[0].character,
+            'Ŝ'
+        );
+
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+        decoder.push_str(&encoded, &mut text).unwrap();
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "QSL ヨロシク 73");
+
+        let latin_only = MorseCode::new(Options {
+            wabun_switching: true,
+            allowed: Some(Cow::Borrowed(&[MorseCharacterSet::Latin])),
+            ..Default::default()
+        });
+        assert_eq!(latin_only.decode("-..--- --.- ...-. --.-"), "QQ");
+        assert_eq!(latin_only.encode("Q"), "--.-");
+    }
+
+    #[test]
+    fn converts_kana_for_wabun() {
+        let morse_code = MorseCode::new(Options {
+            priority: MorseCharacterSet::Japanese,
+            ..Default::default()
+        });
+        let encoded = morse_code.encode("がっぱ ヴ");
+        assert_eq!(encoded, morse_code.encode("カ゛ツハ゜ ウ゛"));
+        assert_eq!(morse_code.decode(&encoded), "ガツパ ヴ");
+        assert_eq!(morse_code.decode(&morse_code.encode("ア゛")), "ア゛");
+
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+        decoder.push_str(".-.. ", &mut text).unwrap();
+        assert_eq!(text, "");
+        assert!(decoder.is_pending());
+        decoder.push_str(".. .-.. ", &mut text).unwrap();
+        assert_eq!(text, "ガ");
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "ガカ");
+    }
+
+    #[test]
+    fn spells_hangul_in_jamo() {
+        let morse_code = MorseCode::new(Options {
+            priority: MorseCharacterSet::Korean,
+            ..Default::default()
+        });
+        let encoded = morse_code.encode("괜찮아요 한국");
+        assert_eq!(
+            encoded,
+            morse_code.encode("ㄱㅗㅏㅣㄴㅊㅏㄴㅎㅇㅏㅇㅛ ㅎㅏㄴㄱㅜㄱ")
+        );
+        assert_eq!(morse_code.decode(&encoded), "괜찮아요 한국");
+
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+        decoder
+            .push_str(&morse_code.encode("ㅎㅏㄴ"), &mut text)
+            .unwrap();
+        decoder.push_str(" ", &mut text).unwrap();
+        assert_eq!(text, "");
+        assert!(decoder.is_pending());
+        decoder
+            .push_str(&morse_code.encode("ㄱㅜ"), &mut text)
+            .unwrap();
+        decoder.push_str(" ", &mut text).unwrap();
+        assert_eq!(text, "한");
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "한구");
+    }
+
+    #[test]
+    fn spells_korean_in_skats_letters() {
+        let morse_code = MorseCode::new(Options {
+            priority: MorseCharacterSet::Korean,
+            ..Default::default()
+        });
+        assert_eq!(morse_code.to_skats("괜찮아요, ㅋ!"), "LAEUFCEFJKEKN, X!");
+        assert_eq!(morse_code.from_skats("LAEUFCEFJKEKN, X!"), "괜찮아요, ㅋ!");
+        assert_eq!(morse_code.from_skats("QJEY"), "Q하Y");
+        assert_eq!(
+            MorseCode::default().decode(&morse_code.encode("괜찮아요")),
+            "LAEUFCEFJKEKN"
+        );
+    }
+
+    #[test]
+    fn validates_options() {
+        assert_eq!(Options::builder().build(), Ok(Options::default()));
+        assert_eq!(
+            Options::builder().dot("-").build(),
+            Err(OptionsError::SameSymbol {
+                first: SymbolKind::Dot,
+                second: SymbolKind::Dash,
+                symbol: "-".into()
+            })
+        );
+        assert_eq!(
+            Options::builder().space("").build(),
+            Err(OptionsError::EmptySymbol {
+                kind: SymbolKind::Space
+            })
+        );
+        assert_eq!(
+            MorseCode::try_new(Options {
+                separator: "E".into(),
+                ..Default::default()
+            }),
+            Err(OptionsError::TableCharacter {
+                kind: SymbolKind::Separator,
+                symbol: 'E',
+                set: MorseCharacterSet::Latin
+            })
+        );
+        assert!(MorseCode::try_new(Options {
+            separator: "|".into(),
+            ..Default::default()
+        })
+        .is_ok());
+
+        assert_eq!(
+            Options::builder().dot("a").dash("aa").build(),
+            Err(OptionsError::AmbiguousSymbols {
+                first: SymbolKind::Dot,
+                second: SymbolKind::Dash
+            })
+        );
+        assert_eq!(
+            Options::builder().dot("ab").dash("aba").build(),
+            Err(OptionsError::AmbiguousSymbols {
+                first: SymbolKind::Dot,
+                second: SymbolKind::Dash
+            })
+        );
+        assert_eq!(
+            Options::builder().separator("..").build(),
+            Err(OptionsError::SpelledWithElements {
+                kind: SymbolKind::Separator,
+                symbol: "..".into()
+            })
+        );
+        assert_eq!(
+            Options::builder().space(".-.").build(),
+            Err(OptionsError::SpelledWithElements {
+                kind: SymbolKind::Space,
+                symbol: ".-.".into()
+            })
+        );
+        assert_eq!(
+            Options::builder().dash(". ").build(),
+            Err(OptionsError::AmbiguousSymbols {
+                first: SymbolKind::Dot,
+                second: SymbolKind::Dash
+            })
+        );
+        assert!(Options::builder().dot(".").dash(".-").build().is_ok());
+        assert!(Options::builder()
+            .separator(" ")
+            .space("   ")
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn handles_word_gaps() {
+        let morse_code = MorseCode::default();
+        for morse in [".-/-...", ".-  /  -...", ".- / / -...", ".- /\n/ -..."] {
+            assert_eq!(morse_code.decode(morse), "A B", "{morse:?}");
+        }
+        let cyrillic = MorseCode::new(Options {
+            priority: MorseCharacterSet::Cyrillic,
+            ..Default::default()
+        });
+        assert_eq!(cyrillic.decode(".-/-..."), "А Б");
+
+        assert_eq!(morse_code.encode("  a  b  "), ".- / -...");
+        let skipping = MorseCode::new(Options {
+            invalid_char_policy: InvalidCharPolicy::Skip,
+            ..Default::default()
+        });
+        assert_eq!(skipping.encode("a % b"), ".- / -...");
+        assert_eq!(skipping.encode("% a"), ".-");
+
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+        for chunk in [".- /", " / -...", "/"] {
+            decoder.push_str(chunk, &mut text).unwrap();
+        }
+        decoder.push_element(Element::WordGap, &mut text).unwrap();
+        assert_eq!(text, "A B ");
+    }
+
+    #[test]
+    fn handles_multi_character_symbols() {
+        let morse_code = MorseCode::try_new(
+            Options::builder()
+                .dot("di")
+                .dash("dah")
+                .separator(" ")
+                .space("   ")
+                .build()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(morse_code.encode("AN T"), "didah dahdi     dah");
+        assert_eq!(morse_code.decode("didah dahdi     dah"), "AN T");
+        assert_eq!(morse_code.decode("didah   dah"), "A T");
+
+        let morse_code = MorseCode::new(Options {
+            dot: "·".into(),
+            dash: "−".into(),
+            separator: "\u{2009}".into(),
+            space: " / ".into(),
+            ..Default::default()
+        });
+        let encoded = morse_code.encode("SOS SOS");
+        assert_eq!(
+            encoded,
+            "···\u{2009}−−−\u{2009}···\u{2009} / \u{2009}···\u{2009}−−−\u{2009}···"
+        );
+        assert_eq!(morse_code.decode(&encoded), "SOS SOS");
+
+        // Overlapping prefixes are resolved by the longest symbol.
+        let morse_code = MorseCode::new(Options {
+            dot: ".".into(),
+            dash: ".-".into(),
+            ..Default::default()
+        });
+        assert_eq!(morse_code.encode("R"), "..-.");
+        assert_eq!(morse_code.decode("..-."), "R");
+        assert_eq!(
+            morse_code.try_decode("..-.x").unwrap_err().tokens()[0].candidates,
+            ['L', 'R', 'Ä']
+        );
+    }
 }
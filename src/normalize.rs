@@ -0,0 +1,416 @@
+//! Normalization of input text, so that equivalent spellings of a character encode alike.
+//!
+//! This is not a complete implementation of Unicode normalization, which would need the whole
+//! Unicode character database. It orders and composes the combining marks that the built-in tables
+//! and transliterations know about, and folds the compatibility forms most often met in practice.
+
+/// Canonical compositions of a base character and a combining mark, sorted by base and then by
+/// mark, covering every precomposed character of the built-in tables and transliterations in both
+/// cases.
+static COMPOSITIONS: [(char, char, char); 257] = [
+    ('A', '\u{300}', 'À'),
+    ('A', '\u{301}', 'Á'),
+    ('A', '\u{302}', 'Â'),
+    ('A', '\u{303}', 'Ã'),
+    ('A', '\u{304}', 'Ā'),
+    ('A', '\u{306}', 'Ă'),
+    ('A', '\u{308}', 'Ä'),
+    ('A', '\u{309}', 'Ả'),
+    ('A', '\u{30A}', 'Å'),
+    ('A', '\u{30C}', 'Ǎ'),
+    ('A', '\u{323}', 'Ạ'),
+    ('A', '\u{328}', 'Ą'),
+    ('C', '\u{301}', 'Ć'),
+    ('C', '\u{302}', 'Ĉ'),
+    ('C', '\u{307}', 'Ċ'),
+    ('C', '\u{30C}', 'Č'),
+    ('C', '\u{327}', 'Ç'),
+    ('D', '\u{30C}', 'Ď'),
+    ('E', '\u{300}', 'È'),
+    ('E', '\u{301}', 'É'),
+    ('E', '\u{302}', 'Ê'),
+    ('E', '\u{303}', 'Ẽ'),
+    ('E', '\u{304}', 'Ē'),
+    ('E', '\u{306}', 'Ĕ'),
+    ('E', '\u{307}', 'Ė'),
+    ('E', '\u{308}', 'Ë'),
+    ('E', '\u{309}', 'Ẻ'),
+    ('E', '\u{30C}', 'Ě'),
+    ('E', '\u{323}', 'Ẹ'),
+    ('E', '\u{328}', 'Ę'),
+    ('G', '\u{302}', 'Ĝ'),
+    ('G', '\u{306}', 'Ğ'),
+    ('G', '\u{307}', 'Ġ'),
+    ('G', '\u{327}', 'Ģ'),
+    ('H', '\u{302}', 'Ĥ'),
+    ('I', '\u{300}', 'Ì'),
+    ('I', '\u{301}', 'Í'),
+    ('I', '\u{302}', 'Î'),
+    ('I', '\u{303}', 'Ĩ'),
+    ('I', '\u{304}', 'Ī'),
+    ('I', '\u{306}', 'Ĭ'),
+    ('I', '\u{307}', 'İ'),
+    ('I', '\u{308}', 'Ï'),
+    ('I', '\u{309}', 'Ỉ'),
+    ('I', '\u{30C}', 'Ǐ'),
+    ('I', '\u{323}', 'Ị'),
+    ('I', '\u{328}', 'Į'),
+    ('J', '\u{302}', 'Ĵ'),
+    ('K', '\u{327}', 'Ķ'),
+    ('L', '\u{301}', 'Ĺ'),
+    ('L', '\u{30C}', 'Ľ'),
+    ('L', '\u{327}', 'Ļ'),
+    ('N', '\u{301}', 'Ń'),
+    ('N', '\u{303}', 'Ñ'),
+    ('N', '\u{30C}', 'Ň'),
+    ('N', '\u{327}', 'Ņ'),
+    ('O', '\u{300}', 'Ò'),
+    ('O', '\u{301}', 'Ó'),
+    ('O', '\u{302}', 'Ô'),
+    ('O', '\u{303}', 'Õ'),
+    ('O', '\u{304}', 'Ō'),
+    ('O', '\u{306}', 'Ŏ'),
+    ('O', '\u{308}', 'Ö'),
+    ('O', '\u{309}', 'Ỏ'),
+    ('O', '\u{30B}', 'Ő'),
+    ('O', '\u{30C}', 'Ǒ'),
+    ('O', '\u{31B}', 'Ơ'),
+    ('O', '\u{323}', 'Ọ'),
+    ('R', '\u{301}', 'Ŕ'),
+    ('R', '\u{30C}', 'Ř'),
+    ('R', '\u{327}', 'Ŗ'),
+    ('S', '\u{301}', 'Ś'),
+    ('S', '\u{302}', 'Ŝ'),
+    ('S', '\u{30C}', 'Š'),
+    ('S', '\u{326}', 'Ș'),
+    ('S', '\u{327}', 'Ş'),
+    ('T', '\u{30C}', 'Ť'),
+    ('T', '\u{326}', 'Ț'),
+    ('T', '\u{327}', 'Ţ'),
+    ('U', '\u{300}', 'Ù'),
+    ('U', '\u{301}', 'Ú'),
+    ('U', '\u{302}', 'Û'),
+    ('U', '\u{303}', 'Ũ'),
+    ('U', '\u{304}', 'Ū'),
+    ('U', '\u{306}', 'Ŭ'),
+    ('U', '\u{308}', 'Ü'),
+    ('U', '\u{309}', 'Ủ'),
+    ('U', '\u{30A}', 'Ů'),
+    ('U', '\u{30B}', 'Ű'),
+    ('U', '\u{30C}', 'Ǔ'),
+    ('U', '\u{31B}', 'Ư'),
+    ('U', '\u{323}', 'Ụ'),
+    ('U', '\u{328}', 'Ų'),
+    ('W', '\u{302}', 'Ŵ'),
+    ('Y', '\u{300}', 'Ỳ'),
+    ('Y', '\u{301}', 'Ý'),
+    ('Y', '\u{302}', 'Ŷ'),
+    ('Y', '\u{303}', 'Ỹ'),
+    ('Y', '\u{308}', 'Ÿ'),
+    ('Y', '\u{309}', 'Ỷ'),
+    ('Y', '\u{323}', 'Ỵ'),
+    ('Z', '\u{301}', 'Ź'),
+    ('Z', '\u{307}', 'Ż'),
+    ('Z', '\u{30C}', 'Ž'),
+    ('a', '\u{300}', 'à'),
+    ('a', '\u{301}', 'á'),
+    ('a', '\u{302}', 'â'),
+    ('a', '\u{303}', 'ã'),
+    ('a', '\u{304}', 'ā'),
+    ('a', '\u{306}', 'ă'),
+    ('a', '\u{308}', 'ä'),
+    ('a', '\u{309}', 'ả'),
+    ('a', '\u{30A}', 'å'),
+    ('a', '\u{30C}', 'ǎ'),
+    ('a', '\u{323}', 'ạ'),
+    ('a', '\u{328}', 'ą'),
+    ('c', '\u{301}', 'ć'),
+    ('c', '\u{302}', 'ĉ'),
+    ('c', '\u{307}', 'ċ'),
+    ('c', '\u{30C}', 'č'),
+    ('c', '\u{327}', 'ç'),
+    ('d', '\u{30C}', 'ď'),
+    ('e', '\u{300}', 'è'),
+    ('e', '\u{301}', 'é'),
+    ('e', '\u{302}', 'ê'),
+    ('e', '\u{303}', 'ẽ'),
+    ('e', '\u{304}', 'ē'),
+    ('e', '\u{306}', 'ĕ'),
+    ('e', '\u{307}', 'ė'),
+    ('e', '\u{308}', 'ë'),
+    ('e', '\u{309}', 'ẻ'),
+    ('e', '\u{30C}', 'ě'),
+    ('e', '\u{323}', 'ẹ'),
+    ('e', '\u{328}', 'ę'),
+    ('g', '\u{302}', 'ĝ'),
+    ('g', '\u{306}', 'ğ'),
+    ('g', '\u{307}', 'ġ'),
+    ('g', '\u{327}', 'ģ'),
+    ('h', '\u{302}', 'ĥ'),
+    ('i', '\u{300}', 'ì'),
+    ('i', '\u{301}', 'í'),
+    ('i', '\u{302}', 'î'),
+    ('i', '\u{303}', 'ĩ'),
+    ('i', '\u{304}', 'ī'),
+    ('i', '\u{306}', 'ĭ'),
+    ('i', '\u{308}', 'ï'),
+    ('i', '\u{309}', 'ỉ'),
+    ('i', '\u{30C}', 'ǐ'),
+    ('i', '\u{323}', 'ị'),
+    ('i', '\u{328}', 'į'),
+    ('j', '\u{302}', 'ĵ'),
+    ('k', '\u{327}', 'ķ'),
+    ('l', '\u{301}', 'ĺ'),
+    ('l', '\u{30C}', 'ľ'),
+    ('l', '\u{327}', 'ļ'),
+    ('n', '\u{301}', 'ń'),
+    ('n', '\u{303}', 'ñ'),
+    ('n', '\u{30C}', 'ň'),
+    ('n', '\u{327}', 'ņ'),
+    ('o', '\u{300}', 'ò'),
+    ('o', '\u{301}', 'ó'),
+    ('o', '\u{302}', 'ô'),
+    ('o', '\u{303}', 'õ'),
+    ('o', '\u{304}', 'ō'),
+    ('o', '\u{306}', 'ŏ'),
+    ('o', '\u{308}', 'ö'),
+    ('o', '\u{309}', 'ỏ'),
+    ('o', '\u{30B}', 'ő'),
+    ('o', '\u{30C}', 'ǒ'),
+    ('o', '\u{31B}', 'ơ'),
+    ('o', '\u{323}', 'ọ'),
+    ('r', '\u{301}', 'ŕ'),
+    ('r', '\u{30C}', 'ř'),
+    ('r', '\u{327}', 'ŗ'),
+    ('s', '\u{301}', 'ś'),
+    ('s', '\u{302}', 'ŝ'),
+    ('s', '\u{30C}', 'š'),
+    ('s', '\u{326}', 'ș'),
+    ('s', '\u{327}', 'ş'),
+    ('t', '\u{30C}', 'ť'),
+    ('t', '\u{326}', 'ț'),
+    ('t', '\u{327}', 'ţ'),
+    ('u', '\u{300}', 'ù'),
+    ('u', '\u{301}', 'ú'),
+    ('u', '\u{302}', 'û'),
+    ('u', '\u{303}', 'ũ'),
+    ('u', '\u{304}', 'ū'),
+    ('u', '\u{306}', 'ŭ'),
+    ('u', '\u{308}', 'ü'),
+    ('u', '\u{309}', 'ủ'),
+    ('u', '\u{30A}', 'ů'),
+    ('u', '\u{30B}', 'ű'),
+    ('u', '\u{30C}', 'ǔ'),
+    ('u', '\u{31B}', 'ư'),
+    ('u', '\u{323}', 'ụ'),
+    ('u', '\u{328}', 'ų'),
+    ('w', '\u{302}', 'ŵ'),
+    ('y', '\u{300}', 'ỳ'),
+    ('y', '\u{301}', 'ý'),
+    ('y', '\u{302}', 'ŷ'),
+    ('y', '\u{303}', 'ỹ'),
+    ('y', '\u{308}', 'ÿ'),
+    ('y', '\u{309}', 'ỷ'),
+    ('y', '\u{323}', 'ỵ'),
+    ('z', '\u{301}', 'ź'),
+    ('z', '\u{307}', 'ż'),
+    ('z', '\u{30C}', 'ž'),
+    ('Â', '\u{300}', 'Ầ'),
+    ('Â', '\u{301}', 'Ấ'),
+    ('Â', '\u{303}', 'Ẫ'),
+    ('Â', '\u{309}', 'Ẩ'),
+    ('Ê', '\u{300}', 'Ề'),
+    ('Ê', '\u{301}', 'Ế'),
+    ('Ê', '\u{303}', 'Ễ'),
+    ('Ê', '\u{309}', 'Ể'),
+    ('Ô', '\u{300}', 'Ồ'),
+    ('Ô', '\u{301}', 'Ố'),
+    ('Ô', '\u{303}', 'Ỗ'),
+    ('Ô', '\u{309}', 'Ổ'),
+    ('â', '\u{300}', 'ầ'),
+    ('â', '\u{301}', 'ấ'),
+    ('â', '\u{303}', 'ẫ'),
+    ('â', '\u{309}', 'ẩ'),
+    ('ê', '\u{300}', 'ề'),
+    ('ê', '\u{301}', 'ế'),
+    ('ê', '\u{303}', 'ễ'),
+    ('ê', '\u{309}', 'ể'),
+    ('ô', '\u{300}', 'ồ'),
+    ('ô', '\u{301}', 'ố'),
+    ('ô', '\u{303}', 'ỗ'),
+    ('ô', '\u{309}', 'ổ'),
+    ('Ă', '\u{300}', 'Ằ'),
+    ('Ă', '\u{301}', 'Ắ'),
+    ('Ă', '\u{303}', 'Ẵ'),
+    ('Ă', '\u{309}', 'Ẳ'),
+    ('ă', '\u{300}', 'ằ'),
+    ('ă', '\u{301}', 'ắ'),
+    ('ă', '\u{303}', 'ẵ'),
+    ('ă', '\u{309}', 'ẳ'),
+    ('І', '\u{308}', 'Ї'),
+    ('Е', '\u{308}', 'Ё'),
+    ('И', '\u{306}', 'Й'),
+    ('К', '\u{301}', 'Ќ'),
+    ('У', '\u{306}', 'Ў'),
+    ('е', '\u{308}', 'ё'),
+    ('и', '\u{306}', 'й'),
+    ('к', '\u{301}', 'ќ'),
+    ('у', '\u{306}', 'ў'),
+    ('і', '\u{308}', 'ї'),
+    ('Ạ', '\u{302}', 'Ậ'),
+    ('Ạ', '\u{306}', 'Ặ'),
+    ('ạ', '\u{302}', 'ậ'),
+    ('ạ', '\u{306}', 'ặ'),
+    ('Ẹ', '\u{302}', 'Ệ'),
+    ('ẹ', '\u{302}', 'ệ'),
+    ('Ọ', '\u{302}', 'Ộ'),
+    ('ọ', '\u{302}', 'ộ'),
+];
+
+/// The most combining marks put in canonical order after a base character. Marks past it are left
+/// where they are.
+const MAX_MARKS: usize = 8;
+
+/// Halfwidth katakana and punctuation, from U+FF61 to U+FF9F, as their standard forms. The
+/// halfwidth sound marks become the combining ones.
+const HALFWIDTH_KATAKANA: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '\u{3099}',
+    '\u{309A}',
+];
+
+/// Returns the characters of `chars` in composed form, each with the index of the first character
+/// it was composed from.
+///
+/// As in Unicode normalization form C, the combining marks following a base character are put in
+/// canonical order, then composed with it unless blocked by a mark of the same class left
+/// uncomposed. Only the marks of [`COMPOSITIONS`] and the kana sound marks have a known combining
+/// class; any other character stops the run of marks. Fullwidth and halfwidth forms and
+/// superscript and subscript digits are first replaced by their plain counterparts.
+pub(crate) fn normalize<I: Iterator<Item = (usize, char)>>(
+    chars: I,
+) -> impl Iterator<Item = (usize, char)> {
+    let mut chars = chars
+        .map(|(index, c)| (index, fold_compatibility(c)))
+        .peekable();
+    let mut marks = [(0, '\0'); MAX_MARKS];
+    let (mut uncomposed, mut emitted) = (0, 0);
+    core::iter::from_fn(move || {
+        if emitted < uncomposed {
+            emitted += 1;
+            return Some(marks[emitted - 1]);
+        }
+        let (index, mut c) = chars.next()?;
+
+        let mut len = 0;
+        while len < MAX_MARKS {
+            let Some(mark) = chars.next_if(|&(_, mark)| combining_class(mark) != 0) else {
+                break;
+            };
+            // Marks are ordered by combining class, and otherwise kept in the order they came.
+            let class = combining_class(mark.1);
+            let at = marks[..len]
+                .iter()
+                .rposition(|&(_, m)| combining_class(m) <= class)
+                .map_or(0, |i| i + 1);
+            marks.copy_within(at..len, at + 1);
+            marks[at] = mark;
+            len += 1;
+        }
+
+        // The class of the last mark left uncomposed, which blocks marks of the same class.
+        let mut blocking = 0;
+        (uncomposed, emitted) = (0, 0);
+        for i in 0..len {
+            let (mark_index, mark) = marks[i];
+            let class = combining_class(mark);
+            match compose(c, mark).filter(|_| blocking < class) {
+                Some(composed) => c = composed,
+                None => {
+                    marks[uncomposed] = (mark_index, mark);
+                    uncomposed += 1;
+                    blocking = class;
+                }
+            }
+        }
+        Some((index, c))
+    })
+}
+
+/// Returns the canonical combining class of `c` if it is one of the marks normalization knows
+/// about, or else 0.
+const fn combining_class(c: char) -> u8 {
+    match c {
+        '\u{300}'..='\u{314}' => 230,
+        '\u{31B}' => 216,
+        '\u{323}'..='\u{326}' => 220,
+        '\u{327}' | '\u{328}' => 202,
+        '\u{3099}' | '\u{309A}' => 8,
+        _ => 0,
+    }
+}
+
+/// Returns the precomposed character made of `base` and the combining `mark`, if it is known.
+fn compose(base: char, mark: char) -> Option<char> {
+    COMPOSITIONS
+        .binary_search_by(|&(b, m, _)| (b, m).cmp(&(base, mark)))
+        .ok()
+        .map(|i| COMPOSITIONS[i].2)
+}
+
+/// Replaces a compatibility form of a character by the character itself.
+const fn fold_compatibility(c: char) -> char {
+    let folded = match c {
+        // Fullwidth ASCII, as typed with East Asian input methods.
+        '\u{FF01}'..='\u{FF5E}' => c as u32 - 0xFEE0,
+        // Halfwidth katakana, as found in legacy Japanese text.
+        '\u{FF61}'..='\u{FF9F}' => return HALFWIDTH_KATAKANA[(c as u32 - 0xFF61) as usize],
+        '\u{3000}' => ' ' as u32,
+        '⁰' => '0' as u32,
+        '¹' => '1' as u32,
+        '²' => '2' as u32,
+        '³' => '3' as u32,
+        '⁴'..='⁹' => c as u32 - '⁴' as u32 + '4' as u32,
+        '₀'..='₉' => c as u32 - '₀' as u32 + '0' as u32,
+        _ => return c,
+    };
+    match char::from_u32(folded) {
+        Some(folded) => folded,
+        None => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn composes_and_folds_characters() {
+        let normalized = |text: &str| {
+            normalize(text.chars().enumerate())
+                .map(|(_, c)| c)
+                .collect::<String>()
+        };
+        assert_eq!(normalized("E\u{301}te\u{301}"), "Été");
+        assert_eq!(normalized("A\u{302}\u{301}a\u{323}\u{302}"), "Ấậ");
+        assert_eq!(normalized("A\u{302}\u{323}a\u{302}\u{323}"), "Ậậ");
+        assert_eq!(
+            normalized("A\u{301}\u{301}C\u{301}\u{327}"),
+            "Á\u{301}Ç\u{301}"
+        );
+        assert_eq!(normalized("ｶﾞﾝﾊﾞﾚ｡"), "カ\u{3099}ンハ\u{3099}レ。");
+        assert_eq!(normalized("Q\u{301}"), "Q\u{301}");
+        assert_eq!(normalized("ＳＯＳ　Ｘ²₃"), "SOS X23");
+
+        let indices = normalize("n\u{303}o".chars().enumerate())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        assert_eq!(indices, [0, 2]);
+        assert!(COMPOSITIONS
+            .windows(2)
+            .all(|w| (w[0].0, w[0].1) < (w[1].0, w[1].1)));
+    }
+}
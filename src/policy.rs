@@ -0,0 +1,269 @@
+//! Policies deciding what happens to input that cannot be encoded or decoded.
+
+use alloc::{borrow::Cow, string::String, sync::Arc};
+use core::fmt;
+
+use crate::translit::transliterate;
+
+/// The text a policy substitutes for an invalid character or an unknown sequence.
+///
+/// Single characters are kept apart from strings so that the built-in policies never allocate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Replacement {
+    /// A single character.
+    Char(char),
+    /// Any text, possibly empty.
+    Text(Cow<'static, str>),
+}
+
+impl IntoIterator for Replacement {
+    type Item = char;
+    type IntoIter = ReplacementChars;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ReplacementChars {
+            replacement: self,
+            offset: 0,
+        }
+    }
+}
+
+/// An owning iterator over the characters of a [`Replacement`].
+#[derive(Debug, Clone)]
+pub(crate) struct ReplacementChars {
+    replacement: Replacement,
+    /// The byte offset of the next character.
+    offset: usize,
+}
+
+impl Iterator for ReplacementChars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = match &self.replacement {
+            Replacement::Char(c) => Some(*c).filter(|_| self.offset == 0)?,
+            Replacement::Text(text) => text[self.offset..].chars().next()?,
+        };
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+}
+
+impl fmt::Display for Replacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(c) => fmt::Write::write_char(f, *c),
+            Self::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+/// A handler deciding how an invalid character is encoded.
+///
+/// The handler receives a character that has no Morse code representation and returns the text to
+/// encode in its place, or `None` to reject the character. Returning an empty string drops the
+/// character from the output.
+///
+/// This trait is implemented for every closure of type `Fn(char) -> Option<String>`, so handlers
+/// can capture state such as a counter of rejected characters.
+pub trait InvalidCharHandler: Send + Sync {
+    /// Returns the replacement text for `c`, or `None` to reject it.
+    fn handle(&self, c: char) -> Option<String>;
+}
+
+impl<F> InvalidCharHandler for F
+where
+    F: Fn(char) -> Option<String> + Send + Sync,
+{
+    fn handle(&self, c: char) -> Option<String> {
+        self(c)
+    }
+}
+
+/// Determines how [`MorseCode::encode`](crate::MorseCode::encode) treats characters that have no
+/// Morse code representation.
+///
+/// Replacement text produced by a policy is itself encoded; any of its characters that have no
+/// Morse code representation are emitted unchanged.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InvalidCharPolicy {
+    /// Rejects the character.
+    ///
+    /// `try_encode` reports it in its error, while `encode` emits the character unchanged.
+    #[default]
+    Error,
+    /// Drops the character from the output.
+    Skip,
+    /// Encodes the given character in place of the invalid one.
+    Replace(char),
+    /// Encodes the closest supported spelling of the character, such as `E` for `Ė` or `"` for `“`.
+    ///
+    /// Characters without a known transliteration are rejected as with [`InvalidCharPolicy::Error`].
+    /// Setting [`Options::transliterate`](crate::Options::transliterate) instead combines
+    /// transliteration with any other policy.
+    Transliterate,
+    /// Delegates the decision to a custom handler.
+    ///
+    /// This variant cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn InvalidCharHandler>),
+}
+
+impl InvalidCharPolicy {
+    /// Creates a policy that delegates to the given handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{InvalidCharPolicy, MorseCode, Options};
+    ///
+    /// let policy = InvalidCharPolicy::custom(|c| (c == '%').then(|| "0/0".into()));
+    /// let morse_code = MorseCode::new(Options {
+    ///     invalid_char_policy: policy,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(morse_code.encode("%"), "----- -..-. -----");
+    /// ```
+    pub fn custom<H: InvalidCharHandler + 'static>(handler: H) -> Self {
+        Self::Custom(Arc::new(handler))
+    }
+
+    /// Returns the text to encode in place of `c`, or `None` if the character is rejected.
+    pub(crate) fn resolve(&self, c: char) -> Option<Replacement> {
+        match self {
+            Self::Error => None,
+            Self::Skip => Some(Replacement::Text(Cow::Borrowed(""))),
+            Self::Replace(replacement) => Some(Replacement::Char(*replacement)),
+            Self::Transliterate => {
+                transliterate(c).map(|text| Replacement::Text(Cow::Borrowed(text)))
+            }
+            Self::Custom(handler) => handler
+                .handle(c)
+                .map(|text| Replacement::Text(Cow::Owned(text))),
+        }
+    }
+}
+
+impl fmt::Debug for InvalidCharPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("Error"),
+            Self::Skip => f.write_str("Skip"),
+            Self::Replace(c) => f.debug_tuple("Replace").field(c).finish(),
+            Self::Transliterate => f.write_str("Transliterate"),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for InvalidCharPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Replace(a), Self::Replace(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for InvalidCharPolicy {}
+
+/// A handler deciding how an unknown Morse code sequence is decoded.
+///
+/// The handler receives a token that does not match any known code, exactly as it appeared in the
+/// input, and returns the text to emit in its place, or `None` to reject it. Returning an empty
+/// string drops the sequence from the output.
+///
+/// This trait is implemented for every closure of type `Fn(&str) -> Option<String>`.
+pub trait UnknownSequenceHandler: Send + Sync {
+    /// Returns the replacement text for `sequence`, or `None` to reject it.
+    fn handle(&self, sequence: &str) -> Option<String>;
+}
+
+impl<F> UnknownSequenceHandler for F
+where
+    F: Fn(&str) -> Option<String> + Send + Sync,
+{
+    fn handle(&self, sequence: &str) -> Option<String> {
+        self(sequence)
+    }
+}
+
+/// Determines how [`MorseCode::decode`](crate::MorseCode::decode) treats sequences that do not
+/// match any known code.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnknownSequencePolicy {
+    /// Rejects the sequence.
+    ///
+    /// `try_decode` reports it in its error, while `decode` emits the sequence unchanged.
+    #[default]
+    Error,
+    /// Drops the sequence from the output.
+    Skip,
+    /// Emits the given character, such as `'�'` or `'#'`, in place of the sequence.
+    Placeholder(char),
+    /// Delegates the decision to a custom handler.
+    ///
+    /// This variant cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn UnknownSequenceHandler>),
+}
+
+impl UnknownSequencePolicy {
+    /// Creates a policy that delegates to the given handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use morsify::{MorseCode, Options, UnknownSequencePolicy};
+    ///
+    /// let policy = UnknownSequencePolicy::custom(|sequence: &str| Some(format!("[{sequence}]")));
+    /// let morse_code = MorseCode::new(Options {
+    ///     unknown_sequence_policy: policy,
+    ///     ..Default::default()
+    /// });
+    ///
+    /// assert_eq!(morse_code.decode(".... ........"), "H[........]");
+    /// ```
+    pub fn custom<H: UnknownSequenceHandler + 'static>(handler: H) -> Self {
+        Self::Custom(Arc::new(handler))
+    }
+
+    /// Returns the text to emit in place of `sequence`, or `None` if the sequence is rejected.
+    pub(crate) fn resolve(&self, sequence: &str) -> Option<Replacement> {
+        match self {
+            Self::Error => None,
+            Self::Skip => Some(Replacement::Text(Cow::Borrowed(""))),
+            Self::Placeholder(c) => Some(Replacement::Char(*c)),
+            Self::Custom(handler) => handler
+                .handle(sequence)
+                .map(|text| Replacement::Text(Cow::Owned(text))),
+        }
+    }
+}
+
+impl fmt::Debug for UnknownSequencePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("Error"),
+            Self::Skip => f.write_str("Skip"),
+            Self::Placeholder(c) => f.debug_tuple("Placeholder").field(c).finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl PartialEq for UnknownSequencePolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Placeholder(a), Self::Placeholder(b)) => a == b,
+            (Self::Custom(a), Self::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => core::mem::discriminant(self) == core::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for UnknownSequencePolicy {}
@@ -0,0 +1,113 @@
+//! A lightweight classification of characters into Unicode scripts.
+
+use core::fmt;
+
+/// The Unicode script a character belongs to.
+///
+/// Only the scripts relevant to the character sets supported by this crate are distinguished;
+/// everything else is reported as [`Script::Unknown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Script {
+    /// Characters shared between scripts, such as digits, punctuation and symbols.
+    Common,
+    /// Combining marks that take the script of the character they attach to.
+    Inherited,
+    /// The Latin script.
+    Latin,
+    /// The Greek script.
+    Greek,
+    /// The Cyrillic script.
+    Cyrillic,
+    /// The Armenian script.
+    Armenian,
+    /// The Hebrew script.
+    Hebrew,
+    /// The Arabic script, including the letters used by Persian.
+    Arabic,
+    /// The Devanagari script.
+    Devanagari,
+    /// The Thai script.
+    Thai,
+    /// The Hangul script.
+    Hangul,
+    /// The Hiragana script.
+    Hiragana,
+    /// The Katakana script.
+    Katakana,
+    /// The Han script.
+    Han,
+    /// A script not covered by the other variants.
+    Unknown,
+}
+
+impl Script {
+    /// Returns the script of the given character.
+    #[must_use]
+    pub const fn of(c: char) -> Self {
+        match c {
+            'A'..='Z' | 'a'..='z' | 'ª' | 'º' | 'À'..='Ö' | 'Ø'..='ö' | 'ø'..='ʯ' => {
+                Self::Latin
+            }
+            '\u{1E00}'..='\u{1EFF}' | '\u{FF21}'..='\u{FF3A}' | '\u{FF41}'..='\u{FF5A}' => {
+                Self::Latin
+            }
+            '\u{0300}'..='\u{036F}' | '\u{3099}' | '\u{309A}' => Self::Inherited,
+            '\u{0370}'..='\u{03FF}' | '\u{1F00}'..='\u{1FFF}' => Self::Greek,
+            '\u{0400}'..='\u{052F}' => Self::Cyrillic,
+            '\u{0530}'..='\u{058F}' => Self::Armenian,
+            '\u{0590}'..='\u{05FF}' => Self::Hebrew,
+            '\u{0600}'..='\u{06FF}' | '\u{0750}'..='\u{077F}' | '\u{FB50}'..='\u{FEFC}' => {
+                Self::Arabic
+            }
+            '\u{0900}'..='\u{097F}' => Self::Devanagari,
+            '\u{0E00}'..='\u{0E7F}' => Self::Thai,
+            '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}' | '\u{AC00}'..='\u{D7AF}' => {
+                Self::Hangul
+            }
+            '\u{3041}'..='\u{3096}' | '\u{309D}'..='\u{309F}' => Self::Hiragana,
+            '\u{30A1}'..='\u{30FA}' | '\u{30FD}'..='\u{30FF}' | '\u{FF66}'..='\u{FF9D}' => {
+                Self::Katakana
+            }
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => Self::Han,
+            '\0'..='\u{02FF}'
+            | '\u{2000}'..='\u{2BFF}'
+            | '\u{3000}'..='\u{3040}'
+            | '\u{309B}'
+            | '\u{309C}'
+            | '\u{30FB}'
+            | '\u{30FC}'
+            | '\u{FF00}'..='\u{FF65}'
+            | '\u{FF9E}'..='\u{FFEF}' => Self::Common,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_characters() {
+        assert_eq!(Script::of('A'), Script::Latin);
+        assert_eq!(Script::of('Ž'), Script::Latin);
+        assert_eq!(Script::of('Ж'), Script::Cyrillic);
+        assert_eq!(Script::of('Ω'), Script::Greek);
+        assert_eq!(Script::of('ש'), Script::Hebrew);
+        assert_eq!(Script::of('ژ'), Script::Arabic);
+        assert_eq!(Script::of('ก'), Script::Thai);
+        assert_eq!(Script::of('한'), Script::Hangul);
+        assert_eq!(Script::of('が'), Script::Hiragana);
+        assert_eq!(Script::of('ア'), Script::Katakana);
+        assert_eq!(Script::of('漢'), Script::Han);
+        assert_eq!(Script::of('7'), Script::Common);
+        assert_eq!(Script::of('%'), Script::Common);
+        assert_eq!(Script::of('\u{0301}'), Script::Inherited);
+    }
+}
@@ -0,0 +1,351 @@
+//! A compact, typed representation of the dots and dashes of a Morse code character.
+
+use core::{fmt, iter::FusedIterator, str::FromStr};
+
+use crate::{Element, ParseSequenceError};
+
+/// The dots and dashes making up the code of a single character.
+///
+/// A sequence is bit-packed into a single integer: below a leading marker bit, every element is
+/// stored as one bit, `0` for a dot and `1` for a dash, the first element being the most
+/// significant. Sequences are therefore cheap to copy and compare, and sort shortest first.
+///
+/// Sequences are written and parsed with `.` for dots and `-` for dashes.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{Element, MorseSequence};
+///
+/// let a: MorseSequence = ".-".parse().unwrap();
+/// assert_eq!(a.len(), 2);
+/// assert_eq!(a.iter().collect::<Vec<_>>(), [Element::Dot, Element::Dash]);
+/// assert_eq!(a.to_string(), ".-");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MorseSequence(u32);
+
+impl MorseSequence {
+    /// The maximum number of elements a sequence can hold.
+    pub const MAX_LEN: usize = u32::BITS as usize - 1;
+
+    /// The empty sequence.
+    pub const EMPTY: Self = Self(1);
+
+    /// Parses a sequence written with `.` for dots and `-` for dashes, in a `const` context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` contains another symbol or is longer than [`MorseSequence::MAX_LEN`].
+    #[must_use]
+    pub const fn from_code(code: &str) -> Self {
+        match Self::parse_with(code, ".", "-") {
+            Some(sequence) => sequence,
+            None => panic!("invalid Morse code sequence"),
+        }
+    }
+
+    /// Parses a sequence written with the given dot and dash symbols.
+    ///
+    /// The symbols may be strings of any length; where both match, the longer one wins.
+    ///
+    /// Returns `None` if `code` contains another symbol, if a symbol is empty, or if `code` is
+    /// longer than [`MorseSequence::MAX_LEN`].
+    #[must_use]
+    pub const fn parse_with(code: &str, dot: &str, dash: &str) -> Option<Self> {
+        let (mut bytes, mut sequence) = (code.as_bytes(), Self::EMPTY);
+        let (dot, dash) = (dot.as_bytes(), dash.as_bytes());
+        if dot.is_empty() || dash.is_empty() {
+            return None;
+        }
+        while !bytes.is_empty() {
+            let is_dot = starts_with(bytes, dot);
+            let is_dash = starts_with(bytes, dash);
+            let (next, rest) = if is_dash && (!is_dot || dash.len() > dot.len()) {
+                (sequence.dash(), bytes.split_at(dash.len()).1)
+            } else if is_dot {
+                (sequence.dot(), bytes.split_at(dot.len()).1)
+            } else {
+                return None;
+            };
+            sequence = match next {
+                Some(next) => next,
+                None => return None,
+            };
+            bytes = rest;
+        }
+        Some(sequence)
+    }
+
+    /// Creates a sequence from its elements, ignoring gaps.
+    ///
+    /// Returns `None` if there are more than [`MorseSequence::MAX_LEN`] dots and dashes.
+    pub fn from_elements<I: IntoIterator<Item = Element>>(elements: I) -> Option<Self> {
+        elements
+            .into_iter()
+            .try_fold(Self::EMPTY, |sequence, element| match element {
+                Element::Dot => sequence.dot(),
+                Element::Dash => sequence.dash(),
+                _ => Some(sequence),
+            })
+    }
+
+    /// Returns the sequence extended with a dot, or `None` if it is already full.
+    #[must_use]
+    pub const fn dot(self) -> Option<Self> {
+        self.push(0)
+    }
+
+    /// Returns the sequence extended with a dash, or `None` if it is already full.
+    #[must_use]
+    pub const fn dash(self) -> Option<Self> {
+        self.push(1)
+    }
+
+    /// Returns the number of elements in the sequence.
+    #[must_use]
+    pub const fn len(self) -> usize {
+        (u32::BITS - 1 - self.0.leading_zeros()) as usize
+    }
+
+    /// Returns `true` if the sequence holds no element.
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 1
+    }
+
+    /// Returns the element at `index`, or `None` if the index is out of bounds.
+    #[must_use]
+    pub const fn get(self, index: usize) -> Option<Element> {
+        if index >= self.len() {
+            return None;
+        }
+        if (self.0 >> (self.len() - 1 - index)) & 1 == 0 {
+            Some(Element::Dot)
+        } else {
+            Some(Element::Dash)
+        }
+    }
+
+    /// Returns an iterator over the dots and dashes of the sequence.
+    #[must_use]
+    pub const fn iter(self) -> SequenceIter {
+        SequenceIter {
+            sequence: self,
+            front: 0,
+            back: self.len(),
+        }
+    }
+
+    /// Returns `true` if the sequence begins with the elements of `prefix`.
+    #[must_use]
+    pub const fn starts_with(self, prefix: Self) -> bool {
+        let len = self.len();
+        let prefix_len = prefix.len();
+        len >= prefix_len && self.0 >> (len - prefix_len) == prefix.0
+    }
+
+    /// Returns a value that displays the sequence with the given dot and dash symbols.
+    #[must_use]
+    pub const fn display_with<'a>(self, dot: &'a str, dash: &'a str) -> SequenceDisplay<'a> {
+        SequenceDisplay {
+            sequence: self,
+            dot,
+            dash,
+        }
+    }
+
+    /// Returns the position of the sequence in the dichotomic tree, where the root is at `1` and
+    /// the children of node `i` are at `2 * i` and `2 * i + 1`.
+    pub(crate) const fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the sequence at `index` in the dichotomic tree, the inverse of
+    /// [`MorseSequence::index`].
+    pub(crate) const fn from_index(index: usize) -> Self {
+        debug_assert!(index != 0 && index <= u32::MAX as usize);
+        Self(index as u32)
+    }
+
+    const fn push(self, bit: u32) -> Option<Self> {
+        if self.len() == Self::MAX_LEN {
+            None
+        } else {
+            Some(Self((self.0 << 1) | bit))
+        }
+    }
+}
+
+impl Default for MorseSequence {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl fmt::Debug for MorseSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MorseSequence(\"{self}\")")
+    }
+}
+
+impl fmt::Display for MorseSequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(".", "-").fmt(f)
+    }
+}
+
+impl FromStr for MorseSequence {
+    type Err = ParseSequenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut sequence = Self::EMPTY;
+        for (index, symbol) in s.char_indices() {
+            sequence = match symbol {
+                '.' => sequence.dot(),
+                '-' => sequence.dash(),
+                _ => return Err(ParseSequenceError::InvalidSymbol { symbol, index }),
+            }
+            .ok_or(ParseSequenceError::TooLong)?;
+        }
+        Ok(sequence)
+    }
+}
+
+impl IntoIterator for MorseSequence {
+    type Item = Element;
+    type IntoIter = SequenceIter;
+
+    fn into_iter(self) -> SequenceIter {
+        self.iter()
+    }
+}
+
+/// An iterator over the dots and dashes of a [`MorseSequence`].
+#[derive(Debug, Clone)]
+pub struct SequenceIter {
+    sequence: MorseSequence,
+    front: usize,
+    back: usize,
+}
+
+impl Iterator for SequenceIter {
+    type Item = Element;
+
+    fn next(&mut self) -> Option<Element> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        self.sequence.get(self.front - 1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for SequenceIter {
+    fn next_back(&mut self) -> Option<Element> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        self.sequence.get(self.back)
+    }
+}
+
+impl ExactSizeIterator for SequenceIter {}
+
+impl FusedIterator for SequenceIter {}
+
+/// Displays a [`MorseSequence`] with custom dot and dash symbols.
+///
+/// Created by [`MorseSequence::display_with`].
+#[derive(Debug, Clone, Copy)]
+pub struct SequenceDisplay<'a> {
+    sequence: MorseSequence,
+    dot: &'a str,
+    dash: &'a str,
+}
+
+impl fmt::Display for SequenceDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.sequence.iter().try_for_each(|element| {
+            f.write_str(if element == Element::Dot {
+                self.dot
+            } else {
+                self.dash
+            })
+        })
+    }
+}
+
+/// Returns `true` if `bytes` starts with `prefix`.
+const fn starts_with(bytes: &[u8], prefix: &[u8]) -> bool {
+    if bytes.len() < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn parses_and_displays() {
+        let sequence = MorseSequence::from_code("-.--.");
+        assert_eq!(sequence.len(), 5);
+        assert_eq!(sequence.to_string(), "-.--.");
+        assert_eq!(sequence.display_with("•", "–").to_string(), "–•––•");
+        assert_eq!("-.--.".parse(), Ok(sequence));
+        assert_eq!(MorseSequence::parse_with("–•––•", "•", "–"), Some(sequence));
+        assert_eq!(MorseSequence::parse_with("–•x", "•", "–"), None);
+        assert_eq!(
+            MorseSequence::parse_with("dahdidahdahdi", "di", "dah"),
+            Some(sequence)
+        );
+        assert_eq!(
+            MorseSequence::parse_with("..-.", ".", ".-"),
+            Some(MorseSequence::from_code(".-."))
+        );
+        assert_eq!(
+            "..x".parse::<MorseSequence>(),
+            Err(ParseSequenceError::InvalidSymbol {
+                symbol: 'x',
+                index: 2
+            })
+        );
+        assert_eq!(
+            ".".repeat(32).parse::<MorseSequence>(),
+            Err(ParseSequenceError::TooLong)
+        );
+        assert_eq!("".parse(), Ok(MorseSequence::EMPTY));
+    }
+
+    #[test]
+    fn iterates_elements() {
+        let sequence = MorseSequence::from_code("..-");
+        assert_eq!(
+            MorseSequence::from_elements(sequence.iter().rev()),
+            Some(MorseSequence::from_code("-.."))
+        );
+        assert_eq!(sequence.get(2), Some(Element::Dash));
+        assert_eq!(sequence.get(3), None);
+        assert!(MorseSequence::EMPTY.is_empty());
+        assert!(sequence.starts_with(MorseSequence::from_code("..")));
+        assert!(sequence.starts_with(MorseSequence::EMPTY));
+        assert!(!sequence.starts_with(MorseSequence::from_code(".-")));
+        assert!(!sequence.starts_with(MorseSequence::from_code("..-.")));
+    }
+}
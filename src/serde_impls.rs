@@ -0,0 +1,231 @@
+//! `serde` support for the types whose serialized form differs from their layout.
+//!
+//! Sequences are written as strings of `.` and `-`, and character sets as maps from characters to
+//! sequences, so that persisted configurations stay readable.
+
+use alloc::borrow::Cow;
+use core::fmt;
+
+use serde::{
+    de::{self, MapAccess, Visitor},
+    ser::SerializeMap,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    CharacterSet, InvalidCharPolicy, MorseCharacterSet, MorseSequence, Options, OptionsError,
+    UnknownSequencePolicy,
+};
+
+/// The fields of [`Options`] as they are deserialized, before they are validated.
+#[derive(Deserialize)]
+#[serde(default)]
+pub(crate) struct UncheckedOptions {
+    dash: Cow<'static, str>,
+    dot: Cow<'static, str>,
+    space: Cow<'static, str>,
+    separator: Cow<'static, str>,
+    priority: MorseCharacterSet,
+    fallback: Cow<'static, [MorseCharacterSet]>,
+    allowed: Option<Cow<'static, [MorseCharacterSet]>>,
+    wabun_switching: bool,
+    invalid_char_policy: InvalidCharPolicy,
+    transliterate: bool,
+    unknown_sequence_policy: UnknownSequencePolicy,
+}
+
+impl Default for UncheckedOptions {
+    fn default() -> Self {
+        let Options {
+            dash,
+            dot,
+            space,
+            separator,
+            priority,
+            fallback,
+            allowed,
+            wabun_switching,
+            invalid_char_policy,
+            transliterate,
+            unknown_sequence_policy,
+        } = Options::new();
+        Self {
+            dash,
+            dot,
+            space,
+            separator,
+            priority,
+            fallback,
+            allowed,
+            wabun_switching,
+            invalid_char_policy,
+            transliterate,
+            unknown_sequence_policy,
+        }
+    }
+}
+
+impl TryFrom<UncheckedOptions> for Options {
+    type Error = OptionsError;
+
+    fn try_from(unchecked: UncheckedOptions) -> Result<Self, OptionsError> {
+        let UncheckedOptions {
+            dash,
+            dot,
+            space,
+            separator,
+            priority,
+            fallback,
+            allowed,
+            wabun_switching,
+            invalid_char_policy,
+            transliterate,
+            unknown_sequence_policy,
+        } = unchecked;
+        let options = Self {
+            dash,
+            dot,
+            space,
+            separator,
+            priority,
+            fallback,
+            allowed,
+            wabun_switching,
+            invalid_char_policy,
+            transliterate,
+            unknown_sequence_policy,
+        };
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+impl Serialize for MorseSequence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MorseSequence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SequenceVisitor;
+
+        impl Visitor<'_> for SequenceVisitor {
+            type Value = MorseSequence;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a Morse code sequence made of `.` and `-`")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<MorseSequence, E> {
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(SequenceVisitor)
+    }
+}
+
+impl Serialize for CharacterSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (character, code) in self.iter() {
+            map.serialize_entry(&character, &code)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for CharacterSet {
+    /// Deserializes a map from characters to sequences. As in the built-in tables and in
+    /// [`CodeTable`](crate::CodeTable)s, several characters may share a code.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SetVisitor;
+
+        impl<'de> Visitor<'de> for SetVisitor {
+            type Value = CharacterSet;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a map from characters to Morse code sequences")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<CharacterSet, A::Error> {
+                let mut set = CharacterSet::new();
+                while let Some((character, code)) = map.next_entry::<char, MorseSequence>()? {
+                    if code.is_empty() {
+                        return Err(de::Error::custom(crate::MappingError::EmptyCode {
+                            character,
+                        }));
+                    }
+                    if set.code(character).is_some() {
+                        return Err(de::Error::custom(format_args!(
+                            "{character:?} is mapped twice"
+                        )));
+                    }
+                    set.insert_shared(character, code);
+                }
+                Ok(set)
+            }
+        }
+
+        deserializer.deserialize_map(SetVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        CharacterSet, CodeTable, InvalidCharPolicy, MorseCharacterSet, MorseCode, MorseSequence,
+        Options, UnknownSequencePolicy,
+    };
+
+    #[test]
+    fn round_trips_options() {
+        let options = Options {
+            dot: "•".into(),
+            priority: MorseCharacterSet::Greek,
+            invalid_char_policy: InvalidCharPolicy::Replace('?'),
+            unknown_sequence_policy: UnknownSequencePolicy::Skip,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dash":"-","dot":"•","space":"/","separator":" ","priority":"Greek","fallback":[],"allowed":null,"wabun_switching":false,"invalid_char_policy":{"Replace":"?"},"transliterate":false,"unknown_sequence_policy":"Skip"}"#
+        );
+        assert_eq!(serde_json::from_str::<Options>(&json).unwrap(), options);
+        assert_eq!(
+            serde_json::from_str::<Options>(r#"{"space":"|"}"#).unwrap(),
+            Options {
+                space: "|".into(),
+                ..Default::default()
+            }
+        );
+
+        assert!(serde_json::from_str::<Options>(r#"{"separator":"-"}"#).is_err());
+        assert!(serde_json::from_str::<Options>(r#"{"dot":"a","dash":"aa"}"#).is_err());
+
+        let custom = Options {
+            invalid_char_policy: InvalidCharPolicy::custom(|_| None),
+            ..Default::default()
+        };
+        assert!(serde_json::to_string(&custom).is_err());
+    }
+
+    #[test]
+    fn round_trips_tables() {
+        let mut set = CharacterSet::new();
+        set.insert('Ω', MorseSequence::from_code("........-"))
+            .unwrap();
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, r#"{"Ω":"........-"}"#);
+        assert_eq!(serde_json::from_str::<CharacterSet>(&json).unwrap(), set);
+        assert!(serde_json::from_str::<CharacterSet>(r#"{"A":".x"}"#).is_err());
+        assert!(serde_json::from_str::<CharacterSet>(r#"{"A":""}"#).is_err());
+
+        let table = MorseCode::default().code_table();
+        let json = serde_json::to_string(&table).unwrap();
+        assert!(json.starts_with(r#"{"sets":{"Latin":{"A":".-","B":"-...","#));
+        assert_eq!(serde_json::from_str::<CodeTable>(&json).unwrap(), table);
+    }
+}
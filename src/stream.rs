@@ -0,0 +1,227 @@
+//! Incremental decoding of Morse code that arrives over time.
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{
+    symbol::{next_symbol, Symbol},
+    DecodeState, Element, MorseCode, MorseSequence,
+};
+
+/// A push-based decoder for live Morse code input, such as a key or a radio receiver.
+///
+/// Input is fed either as chunks of text written with the configured symbols, or as individual
+/// [`Element`]s. Every decoded character is written to the given sink as soon as the gap closing it
+/// has been received, except for a kana that may be followed by a dakuten or handakuten and for the
+/// jamo of a Hangul syllable, which wait for the next character; [`StreamDecoder::flush`] decodes
+/// whatever is left once the input ends.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::{Element, MorseCode};
+///
+/// let morse_code = MorseCode::default();
+/// let mut decoder = morse_code.stream_decoder();
+/// let mut text = String::new();
+///
+/// decoder.push_str("... --", &mut text).unwrap();
+/// assert_eq!(text, "S");
+/// decoder.push_str("- ...", &mut text).unwrap();
+/// assert_eq!(text, "SO");
+/// decoder.push_element(Element::WordGap, &mut text).unwrap();
+/// assert_eq!(text, "SOS ");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamDecoder<'a> {
+    morse_code: &'a MorseCode,
+    /// Input received but not scanned yet, as it may be the beginning of a longer symbol.
+    pending: String,
+    /// The symbols of the token received so far.
+    token: String,
+    /// The dots and dashes of the token received so far, or `None` if it holds anything else.
+    sequence: Option<MorseSequence>,
+    /// What the tokens decoded so far left for the next ones.
+    state: DecodeState,
+    /// Whether the last thing written was a word gap, so that consecutive gaps make a single one.
+    word_gap: bool,
+}
+
+impl<'a> StreamDecoder<'a> {
+    /// Creates a decoder using the tables and options of the given `MorseCode`.
+    #[must_use]
+    pub const fn new(morse_code: &'a MorseCode) -> Self {
+        Self {
+            morse_code,
+            pending: String::new(),
+            token: String::new(),
+            sequence: Some(MorseSequence::EMPTY),
+            state: DecodeState::new(),
+            word_gap: false,
+        }
+    }
+
+    /// Feeds a chunk of Morse code text, written with the configured symbols.
+    ///
+    /// A chunk may end anywhere, including in the middle of a character or of a symbol.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn push_str<W: fmt::Write>(&mut self, chunk: &str, out: &mut W) -> fmt::Result {
+        self.pending.push_str(chunk);
+        self.scan(false, out)
+    }
+
+    /// Feeds a single element.
+    ///
+    /// Gaps within a character are implied by consecutive dots and dashes and may be omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn push_element<W: fmt::Write>(&mut self, element: Element, out: &mut W) -> fmt::Result {
+        self.scan(true, out)?;
+        match element {
+            Element::Dot => self.push_symbol(Symbol::Dot, ""),
+            Element::Dash => self.push_symbol(Symbol::Dash, ""),
+            Element::IntraCharGap => {}
+            Element::CharGap => self.decode(out)?,
+            Element::WordGap => self.word_gap(out)?,
+        }
+        Ok(())
+    }
+
+    /// Decodes the character received so far, if any, as if a gap had closed it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn flush<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        self.scan(true, out)?;
+        self.decode(out)?;
+        self.state.flush(out)
+    }
+
+    /// Returns `true` if part of a character has been received but not decoded yet, or if a
+    /// decoded kana is held back until the next character tells whether it carries a dakuten or
+    /// handakuten.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        !self.token.is_empty() || !self.pending.is_empty() || self.state.is_pending()
+    }
+
+    /// Scans the pending input into symbols, keeping back a possible beginning of a longer symbol
+    /// unless `is_final` is set.
+    fn scan<W: fmt::Write>(&mut self, is_final: bool, out: &mut W) -> fmt::Result {
+        let mut pending = core::mem::take(&mut self.pending);
+        let mut start = 0;
+        while let Some((symbol, len)) =
+            next_symbol(&self.morse_code.options, &pending[start..], is_final)
+        {
+            match symbol {
+                Symbol::Space => self.word_gap(out)?,
+                Symbol::Separator | Symbol::Whitespace => self.decode(out)?,
+                Symbol::Dot | Symbol::Dash | Symbol::Other => {
+                    self.push_symbol(symbol, &pending[start..start + len]);
+                }
+            }
+            start += len;
+        }
+        pending.drain(..start);
+        self.pending = pending;
+        Ok(())
+    }
+
+    /// Appends a symbol to the current token. Dots and dashes are written with the configured
+    /// symbols, anything else as `text`.
+    fn push_symbol(&mut self, symbol: Symbol, text: &str) {
+        let options = &self.morse_code.options;
+        match symbol.element() {
+            Some(Element::Dot) => self.token.push_str(&options.dot),
+            Some(_) => self.token.push_str(&options.dash),
+            None => self.token.push_str(text),
+        }
+        self.sequence = self.sequence.and_then(|sequence| match symbol.element()? {
+            Element::Dot => sequence.dot(),
+            _ => sequence.dash(),
+        });
+    }
+
+    /// Decodes the current token, if any, and writes a word gap unless one was just written.
+    fn word_gap<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        self.decode(out)?;
+        if !self.word_gap {
+            self.word_gap = true;
+            self.state.write_str(" ", out)?;
+        }
+        Ok(())
+    }
+
+    /// Decodes the current token, if any.
+    fn decode<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        if !self.token.is_empty() {
+            self.morse_code
+                .decode_token(&self.token, self.sequence, &mut self.state, out)?;
+            self.token.clear();
+            self.sequence = Some(MorseSequence::EMPTY);
+            self.word_gap = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_chunks() {
+        let morse_code = MorseCode::default();
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+
+        for chunk in [".", "... ", ". .-", ".. .-.", ". --", "- / --- ...-.-"] {
+            decoder.push_str(chunk, &mut text).unwrap();
+        }
+        assert_eq!(text, "HELLO O");
+        assert!(decoder.is_pending());
+
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "HELLO O...-.-");
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn decodes_elements() {
+        let morse_code = MorseCode::default();
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+
+        for (_, element) in morse_code.elements("cq dx") {
+            decoder.push_element(element, &mut text).unwrap();
+        }
+        assert_eq!(text, "CQ D");
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "CQ DX");
+    }
+
+    #[test]
+    fn decodes_symbols_split_across_chunks() {
+        let morse_code = MorseCode::new(crate::Options {
+            dot: "di".into(),
+            dash: "dah".into(),
+            space: " / ".into(),
+            ..Default::default()
+        });
+        let mut decoder = morse_code.stream_decoder();
+        let mut text = String::new();
+
+        for chunk in ["d", "idah d", "ahd", "i ", "/", " da", "h"] {
+            decoder.push_str(chunk, &mut text).unwrap();
+        }
+        assert_eq!(text, "AN ");
+        decoder.flush(&mut text).unwrap();
+        assert_eq!(text, "AN T");
+    }
+}
@@ -0,0 +1,172 @@
+//! Scanning of Morse code text into the symbols configured in [`Options`].
+
+use alloc::vec;
+
+use crate::{Element, Options};
+
+/// A symbol of Morse code text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Symbol {
+    /// The configured dot.
+    Dot,
+    /// The configured dash.
+    Dash,
+    /// The configured space between words.
+    Space,
+    /// The configured separator between characters.
+    Separator,
+    /// Whitespace that is not part of a configured symbol, which separates characters.
+    Whitespace,
+    /// A character that is not part of a configured symbol.
+    Other,
+}
+
+impl Symbol {
+    /// Returns the element the symbol stands for within a character, if any.
+    pub(crate) const fn element(self) -> Option<Element> {
+        match self {
+            Self::Dot => Some(Element::Dot),
+            Self::Dash => Some(Element::Dash),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the symbol is part of a token rather than a gap between tokens.
+    pub(crate) const fn is_token(self) -> bool {
+        matches!(self, Self::Dot | Self::Dash | Self::Other)
+    }
+}
+
+/// Returns the symbol at the start of `input` and its length in bytes.
+///
+/// When several configured symbols match, the longest one wins, so symbols may share prefixes.
+/// Returns `None` if `input` is empty or, unless `is_final` is set, if more input could still turn
+/// it into a longer symbol.
+pub(crate) fn next_symbol(
+    options: &Options,
+    input: &str,
+    is_final: bool,
+) -> Option<(Symbol, usize)> {
+    let symbols = [
+        (Symbol::Dot, &*options.dot),
+        (Symbol::Dash, &*options.dash),
+        (Symbol::Space, &*options.space),
+        (Symbol::Separator, &*options.separator),
+    ];
+    if input.is_empty()
+        || !is_final
+            && symbols
+                .iter()
+                .any(|(_, s)| s.len() > input.len() && s.starts_with(input))
+    {
+        return None;
+    }
+
+    let longest = symbols
+        .iter()
+        .filter(|(_, s)| !s.is_empty() && input.starts_with(s))
+        .fold(
+            None,
+            |longest: Option<(Symbol, usize)>, &(symbol, s)| match longest {
+                Some((_, len)) if len >= s.len() => longest,
+                _ => Some((symbol, s.len())),
+            },
+        );
+    longest.or_else(|| {
+        let c = input.chars().next()?;
+        let symbol = if c.is_whitespace() {
+            Symbol::Whitespace
+        } else {
+            Symbol::Other
+        };
+        Some((symbol, c.len_utf8()))
+    })
+}
+
+/// Returns an iterator over the symbols of `input` and their byte ranges.
+pub(crate) fn symbols<'a>(
+    options: &'a Options,
+    input: &'a str,
+) -> impl Iterator<Item = (Symbol, core::ops::Range<usize>)> + 'a {
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        let (symbol, len) = next_symbol(options, &input[start..], true)?;
+        start += len;
+        Some((symbol, start - len..start))
+    })
+}
+
+/// Returns `true` if `text` is made of one or more of `symbols`, none of which may be empty.
+pub(crate) fn is_sequence_of(text: &str, symbols: &[&str]) -> bool {
+    let mut reachable = vec![false; text.len() + 1];
+    reachable[0] = true;
+    for start in 0..text.len() {
+        if reachable[start] {
+            for symbol in symbols {
+                if text[start..].starts_with(symbol) {
+                    reachable[start + symbol.len()] = true;
+                }
+            }
+        }
+    }
+    !text.is_empty() && reachable[text.len()]
+}
+
+/// Returns `true` if some sequence of one or more of `symbols`, none of which may be empty, starts
+/// with `prefix`.
+pub(crate) fn begins_sequence(prefix: &str, symbols: &[&str]) -> bool {
+    // The ends of `prefix` that remain to be matched once some symbols have been.
+    let mut seen = vec![prefix];
+    let mut pending = vec![prefix];
+    while let Some(rest) = pending.pop() {
+        for symbol in symbols {
+            if symbol.starts_with(rest) {
+                return true;
+            }
+            if let Some(next) = rest.strip_prefix(symbol) {
+                if !seen.contains(&next) {
+                    seen.push(next);
+                    pending.push(next);
+                }
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn matches_longest_symbol() {
+        let options = Options {
+            dot: "di".into(),
+            dash: "dah".into(),
+            separator: " ".into(),
+            space: "   ".into(),
+            ..Default::default()
+        };
+        let scanned = symbols(&options, "didah  dx    di")
+            .map(|(symbol, _)| symbol)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scanned,
+            [
+                Symbol::Dot,
+                Symbol::Dash,
+                Symbol::Separator,
+                Symbol::Separator,
+                Symbol::Other,
+                Symbol::Other,
+                Symbol::Space,
+                Symbol::Separator,
+                Symbol::Dot,
+            ]
+        );
+        assert_eq!(next_symbol(&options, "da", false), None);
+        assert_eq!(next_symbol(&options, "  ", false), None);
+        assert_eq!(next_symbol(&options, "da", true), Some((Symbol::Other, 1)));
+    }
+}
@@ -0,0 +1,587 @@
+//! The built-in Morse code tables of every [`MorseCharacterSet`].
+//!
+//! The tables are plain `static` data: each one lists its characters sorted by code point, for
+//! encoding by binary search, and carries a dichotomic tree computed at compile time, for decoding
+//! by direct indexing. Nothing is allocated or computed at run time.
+
+use crate::{MorseCharacterSet, MorseSequence};
+
+/// The length of the dichotomic tree of a built-in table, enough for codes of up to
+/// [`MAX_CODE_LEN`] elements.
+const TREE_LEN: usize = 2 << MAX_CODE_LEN;
+
+/// The length of the longest code in the built-in tables.
+pub(crate) const MAX_CODE_LEN: usize = 7;
+
+/// The built-in table of a character set.
+#[derive(Debug)]
+pub(crate) struct Table {
+    /// The characters of the table and their codes, sorted by character.
+    entries: &'static [(char, MorseSequence)],
+    /// The characters of the table indexed by [`MorseSequence::index`]. When several characters
+    /// share a code, the one with the lowest code point is kept.
+    tree: [Option<char>; TREE_LEN],
+}
+
+impl Table {
+    /// Builds a table from entries sorted by character.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time, if the entries are not sorted or a code is longer than
+    /// [`MAX_CODE_LEN`].
+    const fn new(entries: &'static [(char, MorseSequence)]) -> Self {
+        let mut tree = [None; TREE_LEN];
+        let mut i = 0;
+        while i < entries.len() {
+            let (c, code) = entries[i];
+            assert!(
+                i == 0 || entries[i - 1].0 < c,
+                "table entries must be sorted by character"
+            );
+            assert!(code.len() <= MAX_CODE_LEN, "table code is too long");
+            if tree[code.index()].is_none() {
+                tree[code.index()] = Some(c);
+            }
+            i += 1;
+        }
+        Self { entries, tree }
+    }
+
+    /// Returns the code of `c`, if the table contains it.
+    pub(crate) fn code(&self, c: char) -> Option<MorseSequence> {
+        self.entries
+            .binary_search_by_key(&c, |&(c, _)| c)
+            .ok()
+            .map(|index| self.entries[index].1)
+    }
+
+    /// Returns the character `sequence` decodes to, if the table contains it.
+    pub(crate) fn character(&self, sequence: MorseSequence) -> Option<char> {
+        self.tree.get(sequence.index()).copied().flatten()
+    }
+
+    /// Returns the characters of the table and their codes, sorted by character.
+    pub(crate) const fn entries(&self) -> &'static [(char, MorseSequence)] {
+        self.entries
+    }
+}
+
+/// Returns the built-in table of `set`, or `None` for [`MorseCharacterSet::Undefined`].
+pub(crate) const fn table(set: MorseCharacterSet) -> Option<&'static Table> {
+    Some(match set {
+        MorseCharacterSet::Undefined => return None,
+        MorseCharacterSet::Latin => &LATIN,
+        MorseCharacterSet::Numbers => &NUMBERS,
+        MorseCharacterSet::Punctuation => &PUNCTUATION,
+        MorseCharacterSet::LatinExtended => &LATIN_EXTENDED,
+        MorseCharacterSet::Cyrillic => &CYRILLIC,
+        MorseCharacterSet::Greek => &GREEK,
+        MorseCharacterSet::Hebrew => &HEBREW,
+        MorseCharacterSet::Arabic => &ARABIC,
+        MorseCharacterSet::Persian => &PERSIAN,
+        MorseCharacterSet::Japanese => &JAPANESE,
+        MorseCharacterSet::Korean => &KOREAN,
+        MorseCharacterSet::Thai => &THAI,
+    })
+}
+
+/// Returns the character sets in lookup order: `priority` first, then the `fallback` sets in the
+/// given order, then every set in declaration order, skipping the sets missing from `allowed` if
+/// given.
+pub(crate) fn lookup_order<'a>(
+    priority: MorseCharacterSet,
+    fallback: &'a [MorseCharacterSet],
+    allowed: Option<&'a [MorseCharacterSet]>,
+) -> impl Iterator<Item = MorseCharacterSet> + 'a {
+    core::iter::once(priority)
+        .chain(fallback.iter().copied())
+        .chain(ALL)
+        .filter(move |set| allowed.is_none_or(|allowed| allowed.contains(set)))
+}
+
+/// The DO prosign, which switches from international Morse code to Wabun code.
+pub(crate) const WABUN_START: MorseSequence = MorseSequence::from_code("-..---");
+
+/// The SN prosign, which switches from Wabun code back to international Morse code.
+pub(crate) const WABUN_END: MorseSequence = MorseSequence::from_code("...-.");
+
+/// Every character set, in declaration order.
+pub(crate) const ALL: [MorseCharacterSet; 13] = [
+    MorseCharacterSet::Undefined,
+    MorseCharacterSet::Latin,
+    MorseCharacterSet::Numbers,
+    MorseCharacterSet::Punctuation,
+    MorseCharacterSet::LatinExtended,
+    MorseCharacterSet::Cyrillic,
+    MorseCharacterSet::Greek,
+    MorseCharacterSet::Hebrew,
+    MorseCharacterSet::Arabic,
+    MorseCharacterSet::Persian,
+    MorseCharacterSet::Japanese,
+    MorseCharacterSet::Korean,
+    MorseCharacterSet::Thai,
+];
+
+/// Shorthand for [`MorseSequence::from_code`], to keep the tables readable.
+const fn code(code: &str) -> MorseSequence {
+    MorseSequence::from_code(code)
+}
+
+/// Morse code representations for Latin alphabet characters.
+static LATIN: Table = Table::new(&[
+    ('A', code(".-")),
+    ('B', code("-...")),
+    ('C', code("-.-.")),
+    ('D', code("-..")),
+    ('E', code(".")),
+    ('F', code("..-.")),
+    ('G', code("--.")),
+    ('H', code("....")),
+    ('I', code("..")),
+    ('J', code(".---")),
+    ('K', code("-.-")),
+    ('L', code(".-..")),
+    ('M', code("--")),
+    ('N', code("-.")),
+    ('O', code("---")),
+    ('P', code(".--.")),
+    ('Q', code("--.-")),
+    ('R', code(".-.")),
+    ('S', code("...")),
+    ('T', code("-")),
+    ('U', code("..-")),
+    ('V', code("...-")),
+    ('W', code(".--")),
+    ('X', code("-..-")),
+    ('Y', code("-.--")),
+    ('Z', code("--..")),
+]);
+
+/// Morse code representations for numeric digits.
+static NUMBERS: Table = Table::new(&[
+    ('0', code("-----")),
+    ('1', code(".----")),
+    ('2', code("..---")),
+    ('3', code("...--")),
+    ('4', code("....-")),
+    ('5', code(".....")),
+    ('6', code("-....")),
+    ('7', code("--...")),
+    ('8', code("---..")),
+    ('9', code("----.")),
+]);
+
+/// Morse code representations for punctuation marks.
+static PUNCTUATION: Table = Table::new(&[
+    ('!', code("-.-.--")),
+    ('"', code(".-..-.")),
+    ('$', code("...-..-")),
+    ('&', code(".-...")),
+    ('\'', code(".----.")),
+    ('(', code("-.--.")),
+    (')', code("-.--.-")),
+    ('+', code(".-.-.")),
+    (',', code("--..--")),
+    ('-', code("-....-")),
+    ('.', code(".-.-.-")),
+    ('/', code("-..-.")),
+    (':', code("---...")),
+    (';', code("-.-.-.")),
+    ('=', code("-...-")),
+    ('?', code("..--..")),
+    ('@', code(".--.-.")),
+    ('_', code("..--.-")),
+    ('¡', code("--...-")),
+    ('¿', code("..-.-")),
+]);
+
+/// Morse code representations for Latin Extended characters.
+static LATIN_EXTENDED: Table = Table::new(&[
+    ('À', code(".--.-")),
+    ('Á', code(".--.-")),
+    ('Â', code(".--.-")),
+    ('Ã', code(".--.-")),
+    ('Ä', code(".-.-")),
+    ('Å', code(".--.-")),
+    ('Æ', code(".-.-")),
+    ('Ç', code("-.-..")),
+    ('È', code(".-..-")),
+    ('É', code("..-..")),
+    ('Ê', code("-..-.")),
+    ('Ë', code("..-..")),
+    ('Ì', code(".---.")),
+    ('Ï', code("-..--")),
+    ('Ð', code("..--.")),
+    ('Ñ', code("--.--")),
+    ('Ò', code("---.")),
+    ('Ó', code("---.")),
+    ('Ô', code("---.")),
+    ('Ö', code("---.")),
+    ('Ø', code("---.")),
+    ('Ù', code("..--")),
+    ('Ü', code("..--")),
+    ('Þ', code(".--..")),
+    ('ß', code("......")),
+    ('Ą', code(".-.-")),
+    ('Ć', code("-.-..")),
+    ('Ĉ', code("-.-..")),
+    ('Č', code("--.")),
+    ('Ę', code("..-..")),
+    ('Ĝ', code("--.-.")),
+    ('Ğ', code("--.-.")),
+    ('Ĥ', code("----")),
+    ('İ', code(".-..-")),
+    ('Ĵ', code(".---.")),
+    ('Ł', code(".-..-")),
+    ('Ń', code("--.--")),
+    ('Ś', code("...-...")),
+    ('Ŝ', code("...-.")),
+    ('Ş', code(".--..")),
+    ('Š', code("----")),
+    ('Ŭ', code("..--")),
+    ('Ź', code("--..-.")),
+    ('Ż', code("--..-")),
+    ('Ž', code("--..-")),
+    ('Ș', code("----")),
+]);
+
+/// Morse code representations for Cyrillic alphabet characters.
+static CYRILLIC: Table = Table::new(&[
+    ('Є', code("..-..")),
+    ('І', code("..")),
+    ('Ї', code(".---.")),
+    ('А', code(".-")),
+    ('Б', code("-...")),
+    ('В', code(".--")),
+    ('Г', code("--.")),
+    ('Д', code("-..")),
+    ('Е', code(".")),
+    ('Ж', code("...-")),
+    ('З', code("--..")),
+    ('И', code("..")),
+    ('Й', code(".---")),
+    ('К', code("-.-")),
+    ('Л', code(".-..")),
+    ('М', code("--")),
+    ('Н', code("-.")),
+    ('О', code("---")),
+    ('П', code(".--.")),
+    ('Р', code(".-.")),
+    ('С', code("...")),
+    ('Т', code("-")),
+    ('У', code("..-")),
+    ('Ф', code("..-.")),
+    ('Х', code("....")),
+    ('Ц', code("-.-.")),
+    ('Ч', code("---.")),
+    ('Ш', code("----")),
+    ('Щ', code("--.-")),
+    ('Ъ', code("--.--")),
+    ('Ы', code("-.--")),
+    ('Ь', code("-..-")),
+    ('Э', code("..-..")),
+    ('Ю', code("..--")),
+    ('Я', code(".-.-")),
+    ('Ґ', code("--.")),
+]);
+
+/// Morse code representations for Greek alphabet characters.
+static GREEK: Table = Table::new(&[
+    ('Α', code(".-")),
+    ('Β', code("-...")),
+    ('Γ', code("--.")),
+    ('Δ', code("-..")),
+    ('Ε', code(".")),
+    ('Ζ', code("--..")),
+    ('Η', code("....")),
+    ('Θ', code("-.-.")),
+    ('Ι', code("..")),
+    ('Κ', code("-.-")),
+    ('Λ', code(".-..")),
+    ('Μ', code("--")),
+    ('Ν', code("-.")),
+    ('Ξ', code("-..-")),
+    ('Ο', code("---")),
+    ('Π', code(".--.")),
+    ('Ρ', code(".-.")),
+    ('Σ', code("...")),
+    ('Τ', code("-")),
+    ('Υ', code("-.--")),
+    ('Φ', code("..-.")),
+    ('Χ', code("----")),
+    ('Ψ', code("--.-")),
+    ('Ω', code(".--")),
+]);
+
+/// Morse code representations for Hebrew script characters.
+static HEBREW: Table = Table::new(&[
+    ('א', code(".-")),
+    ('ב', code("-...")),
+    ('ג', code("--.")),
+    ('ד', code("-..")),
+    ('ה', code("---")),
+    ('ו', code(".")),
+    ('ז', code("--..")),
+    ('ח', code("....")),
+    ('ט', code("..-")),
+    ('י', code("..")),
+    ('כ', code("-.-")),
+    ('ל', code(".-..")),
+    ('מ', code("--")),
+    ('נ', code("-.")),
+    ('ס', code("-.-.")),
+    ('ע', code(".---")),
+    ('פ', code(".--.")),
+    ('צ', code(".--")),
+    ('ק', code("--.-")),
+    ('ר', code(".-.")),
+    ('ש', code("...")),
+    ('ת', code("-")),
+]);
+
+/// Morse code representations for Arabic script characters.
+static ARABIC: Table = Table::new(&[
+    ('ا', code(".-")),
+    ('ب', code("-...")),
+    ('ت', code("-")),
+    ('ث', code("-.-.")),
+    ('ج', code(".---")),
+    ('ح', code("....")),
+    ('خ', code("---")),
+    ('د', code("-..")),
+    ('ذ', code("--..")),
+    ('ر', code(".-.")),
+    ('ز', code("---.")),
+    ('س', code("...")),
+    ('ش', code("----")),
+    ('ص', code("-..-")),
+    ('ض', code("...-")),
+    ('ط', code("..-")),
+    ('ظ', code("-.--")),
+    ('ع', code(".-.-")),
+    ('غ', code("--.")),
+    ('ف', code("..-.")),
+    ('ق', code("--.-")),
+    ('ك', code("-.-")),
+    ('ل', code(".-..")),
+    ('م', code("--")),
+    ('ن', code("-.")),
+    ('ه', code("..-..")),
+    ('و', code(".--")),
+    ('ي', code("..")),
+    ('ﺀ', code(".")),
+]);
+
+/// Morse code representations for Persian script characters.
+static PERSIAN: Table = Table::new(&[
+    ('ا', code(".-")),
+    ('ب', code("-...")),
+    ('ت', code("-")),
+    ('ث', code("-.-.")),
+    ('ج', code(".---")),
+    ('ح', code("....")),
+    ('خ', code("-..-")),
+    ('د', code("-..")),
+    ('ذ', code("...-")),
+    ('ر', code(".-.")),
+    ('ز', code("--..")),
+    ('س', code("...")),
+    ('ش', code("----")),
+    ('ص', code(".-.-")),
+    ('ض', code("..-..")),
+    ('ط', code("..-")),
+    ('ظ', code("-.--")),
+    ('ع', code("---")),
+    ('غ', code("..--")),
+    ('ف', code("..-.")),
+    ('ق', code("---...")),
+    ('ل', code(".-..")),
+    ('م', code("--")),
+    ('ن', code("-.")),
+    ('ه', code(".")),
+    ('و', code(".--")),
+    ('پ', code(".--.")),
+    ('چ', code("---.")),
+    ('ژ', code("--.")),
+    ('ک', code("-.-")),
+    ('گ', code("--.-")),
+    ('ی', code("..")),
+]);
+
+/// Morse code representations for Japanese Katakana characters.
+static JAPANESE: Table = Table::new(&[
+    ('、', code(".-.-.-")),
+    ('。', code(".-.-..")),
+    ('゛', code("..")),
+    ('゜', code("..--.")),
+    ('ア', code("--.--")),
+    ('イ', code(".-")),
+    ('ウ', code("..-")),
+    ('エ', code("-.---")),
+    ('オ', code(".-...")),
+    ('カ', code(".-..")),
+    ('キ', code("-.-..")),
+    ('ク', code("...-")),
+    ('ケ', code("-.--")),
+    ('コ', code("----")),
+    ('サ', code("-.-.-")),
+    ('シ', code("--.-.")),
+    ('ス', code("---.-")),
+    ('セ', code(".---.")),
+    ('ソ', code("---.")),
+    ('タ', code("-.")),
+    ('チ', code("..-.")),
+    ('ツ', code(".--.")),
+    ('テ', code(".-.--")),
+    ('ト', code("..-..")),
+    ('ナ', code(".-.")),
+    ('ニ', code("-.-.")),
+    ('ヌ', code("....")),
+    ('ネ', code("--.-")),
+    ('ノ', code("..--")),
+    ('ハ', code("-...")),
+    ('ヒ', code("--..-")),
+    ('フ', code("--..")),
+    ('ヘ', code(".")),
+    ('ホ', code("-..")),
+    ('マ', code("-..-")),
+    ('ミ', code("..-.-")),
+    ('ム', code("-")),
+    ('メ', code("-...-")),
+    ('モ', code("-..-.")),
+    ('ヤ', code(".--")),
+    ('ユ', code("-..--")),
+    ('ヨ', code("--")),
+    ('ラ', code("...")),
+    ('リ', code("--.")),
+    ('ル', code("-.--.")),
+    ('レ', code("---")),
+    ('ロ', code(".-.-")),
+    ('ワ', code("-.-")),
+    ('ヰ', code(".-..-")),
+    ('ヱ', code(".--..")),
+    ('ヲ', code(".---")),
+    ('ン', code(".-.-.")),
+    ('ー', code(".--.-")),
+    ('（', code("-.--.-")),
+    ('）', code(".-..-.")),
+]);
+
+/// Morse code representations for Korean Hangul characters.
+static KOREAN: Table = Table::new(&[
+    ('ㄱ', code(".-..")),
+    ('ㄴ', code("..-.")),
+    ('ㄷ', code("-...")),
+    ('ㄹ', code("...-")),
+    ('ㅁ', code("--")),
+    ('ㅂ', code(".--")),
+    ('ㅅ', code("--.")),
+    ('ㅇ', code("-.-")),
+    ('ㅈ', code(".--.")),
+    ('ㅊ', code("-.-.")),
+    ('ㅋ', code("-..-")),
+    ('ㅌ', code("--..")),
+    ('ㅍ', code("---")),
+    ('ㅎ', code(".---")),
+    ('ㅏ', code(".")),
+    ('ㅑ', code("..")),
+    ('ㅓ', code("-")),
+    ('ㅕ', code("...")),
+    ('ㅗ', code(".-")),
+    ('ㅛ', code("-.")),
+    ('ㅜ', code("....")),
+    ('ㅠ', code(".-.")),
+    ('ㅡ', code("-..")),
+    ('ㅣ', code("..-")),
+]);
+
+/// Morse code representations for Thai script characters.
+static THAI: Table = Table::new(&[
+    ('ก', code("--.")),
+    ('ข', code("-.-.")),
+    ('ค', code("-.-")),
+    ('ง', code("-.--.")),
+    ('จ', code("-..-.")),
+    ('ฉ', code("----")),
+    ('ช', code("-..-")),
+    ('ซ', code("--..")),
+    ('ญ', code(".---")),
+    ('ด', code("-..")),
+    ('ต', code("-")),
+    ('ถ', code("-.-..")),
+    ('ท', code("-..--")),
+    ('น', code("-.")),
+    ('บ', code("-...")),
+    ('ป', code(".--.")),
+    ('ผ', code("--.-")),
+    ('ฝ', code("-.-.-")),
+    ('พ', code(".--..")),
+    ('ฟ', code("..-.")),
+    ('ม', code("--")),
+    ('ย', code("-.--")),
+    ('ร', code(".-.")),
+    ('ฤ', code(".-.--")),
+    ('ล', code(".-..")),
+    ('ว', code(".--")),
+    ('ส', code("...")),
+    ('ห', code("....")),
+    ('อ', code("-...-")),
+    ('ฮ', code("--.--")),
+    ('ฯ', code("--.-.")),
+    ('ะ', code(".-...")),
+    ('ั', code(".--.-")),
+    ('า', code(".-")),
+    ('ำ', code("...-.")),
+    ('ิ', code("..-..")),
+    ('ี', code("..")),
+    ('ึ', code("..--.")),
+    ('ื', code("..--")),
+    ('ุ', code("..-.-")),
+    ('ู', code("---.")),
+    ('เ', code(".")),
+    ('แ', code(".-.-")),
+    ('โ', code("---")),
+    ('ไ', code(".-..-")),
+    ('ๆ', code("-.---")),
+    ('็', code("---..")),
+    ('่', code("..-")),
+    ('้', code("...-")),
+    ('๊', code("--...")),
+    ('๋', code(".-.-.")),
+    ('์', code("--..-")),
+]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_in_priority_order() {
+        let a = code(".-");
+        assert_eq!(LATIN.code('A'), Some(a));
+        assert_eq!(LATIN.code('А'), None);
+        assert_eq!(LATIN.character(a), Some('A'));
+        assert_eq!(LATIN.character(code("-.-.-.-")), None);
+        let decode = |priority, fallback: &[_], allowed: Option<&[_]>| {
+            lookup_order(priority, fallback, allowed)
+                .filter_map(table)
+                .find_map(|table| table.character(a))
+        };
+        let (cyrillic, greek) = (MorseCharacterSet::Cyrillic, MorseCharacterSet::Greek);
+        assert_eq!(decode(cyrillic, &[], None), Some('А'));
+        assert_eq!(decode(MorseCharacterSet::Undefined, &[], None), Some('A'));
+        assert_eq!(
+            decode(MorseCharacterSet::Numbers, &[greek], None),
+            Some('Α')
+        );
+        assert_eq!(
+            decode(MorseCharacterSet::Latin, &[], Some(&[greek])),
+            Some('Α')
+        );
+        assert_eq!(
+            decode(cyrillic, &[], Some(&[MorseCharacterSet::Numbers])),
+            None
+        );
+    }
+}
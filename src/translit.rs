@@ -0,0 +1,94 @@
+//! Transliteration of characters that have no Morse code representation into ones that do.
+
+use alloc::string::String;
+
+/// A character of the input that was encoded as a replacement, as reported by
+/// [`MorseCode::encode_with_substitutions`](crate::MorseCode::encode_with_substitutions).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Substitution {
+    /// The character as it appeared in the input.
+    pub character: char,
+    /// The index of the character within the input, counted in `char`s.
+    pub index: usize,
+    /// The text encoded in place of the character, empty if it was dropped.
+    pub replacement: String,
+}
+
+/// Returns the closest encodable spelling of `c`, or `None` if no transliteration is known.
+///
+/// Latin letters lose their diacritics and Cyrillic letters are romanized as in ICAO Doc 9303, the
+/// specification of machine-readable travel documents. This is not an ITU scheme: letters are
+/// spelled as they sound, such as `ZH` for `Ж`, rather than as the Latin letters sharing their
+/// Morse code, such as `V` for `Ж`. Characters are expected in upper case, as they are looked up
+/// after the input has been upper-cased.
+pub(crate) const fn transliterate(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        'Ā' | 'Ă' | 'Ǎ' | 'Ạ' | 'Ả' | 'Ấ' | 'Ầ' | 'Ẩ' | 'Ẫ' | 'Ậ' | 'Ắ' | 'Ằ' | 'Ẳ' | 'Ẵ' | 'Ặ' => {
+            "A"
+        }
+        'Ċ' => "C",
+        'Ď' | 'Đ' => "D",
+        'Ē' | 'Ĕ' | 'Ė' | 'Ě' | 'Ẹ' | 'Ẻ' | 'Ẽ' | 'Ế' | 'Ề' | 'Ể' | 'Ễ' | 'Ệ' => {
+            "E"
+        }
+        'Ġ' | 'Ģ' => "G",
+        'Ħ' => "H",
+        'Í' | 'Î' | 'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'Ǐ' | 'Ỉ' | 'Ị' => "I",
+        'Ķ' => "K",
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' => "L",
+        'Ņ' | 'Ň' => "N",
+        'Õ' | 'Ō' | 'Ŏ' | 'Ő' | 'Ơ' | 'Ǒ' | 'Ọ' | 'Ỏ' | 'Ố' | 'Ồ' | 'Ổ' | 'Ỗ' | 'Ộ' => {
+            "O"
+        }
+        'Ŕ' | 'Ŗ' | 'Ř' => "R",
+        'Ţ' | 'Ť' | 'Ŧ' | 'Ț' => "T",
+        'Ú' | 'Û' | 'Ũ' | 'Ū' | 'Ů' | 'Ű' | 'Ų' | 'Ư' | 'Ǔ' | 'Ụ' | 'Ủ' => "U",
+        'Ŵ' => "W",
+        'Ý' | 'Ŷ' | 'Ÿ' | 'Ỳ' | 'Ỵ' | 'Ỷ' | 'Ỹ' => "Y",
+        'А' => "A",
+        'Б' => "B",
+        'В' => "V",
+        'Г' | 'Ґ' => "G",
+        'Д' | 'Ђ' => "D",
+        'Е' | 'Ё' | 'Э' => "E",
+        'Ж' => "ZH",
+        'З' => "Z",
+        'И' | 'Й' | 'І' | 'Ї' => "I",
+        'Ј' => "J",
+        'К' | 'Ќ' => "K",
+        'Л' => "L",
+        'Љ' => "LJ",
+        'М' => "M",
+        'Н' => "N",
+        'Њ' => "NJ",
+        'О' => "O",
+        'П' => "P",
+        'Р' => "R",
+        'С' => "S",
+        'Т' => "T",
+        'Ћ' => "C",
+        'У' | 'Ў' => "U",
+        'Ф' => "F",
+        'Х' => "KH",
+        'Ц' => "TS",
+        'Ч' => "CH",
+        'Џ' | 'Ѕ' => "DZ",
+        'Ш' => "SH",
+        'Щ' => "SHCH",
+        'Ъ' | 'Є' => "IE",
+        'Ы' => "Y",
+        'Ь' => "",
+        'Ю' => "IU",
+        'Я' => "IA",
+        'Œ' => "OE",
+        'Ĳ' => "IJ",
+        '‘' | '’' | '‚' | '‛' | '′' | '`' | '´' => "'",
+        '“' | '”' | '„' | '‟' | '″' | '«' | '»' => "\"",
+        '‐' | '‑' | '‒' | '–' | '—' | '―' | '−' => "-",
+        '…' => "...",
+        '×' => "X",
+        '÷' => "/",
+        _ => return None,
+    };
+    Some(replacement)
+}
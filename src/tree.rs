@@ -0,0 +1,229 @@
+//! Step-by-step navigation of the dichotomic Morse code tree.
+
+use alloc::{collections::btree_set::BTreeSet, vec::Vec};
+
+use crate::{
+    symbol::symbols, tables::MAX_CODE_LEN, CharacterSet, Element, MorseCode, MorseSequence,
+};
+
+/// The dichotomic tree of a [`MorseCode`], in which every dot goes left and every dash goes right.
+///
+/// The tree reads from the same tables [`MorseCode::decode`] uses, so when several characters
+/// share a code, the node holds the one from the `priority` character set.
+///
+/// # Example
+///
+/// ```rust
+/// use morsify::MorseCode;
+///
+/// let morse_code = MorseCode::default();
+/// let tree = morse_code.tree();
+///
+/// let node = tree.root().dot().and_then(|node| node.dash());
+/// assert_eq!(node.and_then(|node| node.current()), Some('A'));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MorseTree<'a> {
+    morse_code: &'a MorseCode,
+}
+
+impl<'a> MorseTree<'a> {
+    /// Creates the tree of the given `MorseCode`.
+    #[must_use]
+    pub const fn new(morse_code: &'a MorseCode) -> Self {
+        Self { morse_code }
+    }
+
+    /// Returns the root of the tree, which corresponds to the empty code.
+    #[must_use]
+    pub const fn root(&self) -> MorseNode<'a> {
+        MorseNode {
+            morse_code: self.morse_code,
+            sequence: MorseSequence::EMPTY,
+        }
+    }
+
+    /// Returns the characters whose code is a single dot or dash away from `token`, in ascending
+    /// order.
+    ///
+    /// A code is a single element away if it can be obtained by changing, inserting or removing one
+    /// element. Symbols of `token` other than the configured dot and dash, and each character that
+    /// is not part of a symbol, count as elements that match nothing.
+    pub(crate) fn near_misses(&self, token: &str) -> Vec<char> {
+        let token = symbols(&self.morse_code.options, token)
+            .map(|(symbol, _)| symbol.element())
+            .collect::<Vec<_>>();
+        let mut candidates = Vec::new();
+        let mut edited = Vec::with_capacity(token.len() + 1);
+
+        for position in 0..=token.len() {
+            for element in [Element::Dot, Element::Dash] {
+                // Change the element at `position`.
+                if position < token.len() && token[position] != Some(element) {
+                    edited.clear();
+                    edited.extend_from_slice(&token);
+                    edited[position] = Some(element);
+                    candidates.extend(self.lookup_edited(&edited));
+                }
+                // Insert an element before `position`.
+                edited.clear();
+                edited.extend_from_slice(&token[..position]);
+                edited.push(Some(element));
+                edited.extend_from_slice(&token[position..]);
+                candidates.extend(self.lookup_edited(&edited));
+            }
+            // Remove the element at `position`.
+            if position < token.len() {
+                edited.clear();
+                edited.extend_from_slice(&token[..position]);
+                edited.extend_from_slice(&token[position + 1..]);
+                candidates.extend(self.lookup_edited(&edited));
+            }
+        }
+
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+
+    /// Looks up a code given as elements, where `None` matches no element.
+    fn lookup_edited(&self, elements: &[Option<Element>]) -> Option<char> {
+        let sequence =
+            MorseSequence::from_elements(elements.iter().copied().collect::<Option<Vec<_>>>()?)?;
+        (!sequence.is_empty())
+            .then(|| self.morse_code.character(sequence))
+            .flatten()
+    }
+}
+
+/// A node of a [`MorseTree`], reached by following a sequence of dots and dashes from the root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MorseNode<'a> {
+    morse_code: &'a MorseCode,
+    sequence: MorseSequence,
+}
+
+impl<'a> MorseNode<'a> {
+    /// Returns the node reached by appending a dot, or `None` if no character lies that way.
+    #[must_use]
+    pub fn dot(self) -> Option<Self> {
+        self.child(self.sequence.dot()?)
+    }
+
+    /// Returns the node reached by appending a dash, or `None` if no character lies that way.
+    #[must_use]
+    pub fn dash(self) -> Option<Self> {
+        self.child(self.sequence.dash()?)
+    }
+
+    /// Returns the character whose code leads to this node, if any.
+    #[must_use]
+    pub fn current(&self) -> Option<char> {
+        self.morse_code.character(self.sequence)
+    }
+
+    /// Returns the dots and dashes leading from the root to this node.
+    #[must_use]
+    pub const fn sequence(&self) -> MorseSequence {
+        self.sequence
+    }
+
+    /// Returns the number of dots and dashes between the root and this node.
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.sequence.len()
+    }
+
+    /// Returns every character whose code continues past this node, shortest codes first.
+    ///
+    /// The character of this node itself is not included.
+    pub fn reachable(&self) -> impl Iterator<Item = char> + 'a {
+        let (morse_code, sequence) = (self.morse_code, self.sequence);
+        let index = sequence.index();
+        // Built-in codes are short enough to visit every node below; longer user-defined codes are
+        // collected from their sets.
+        let short = (1..=MAX_CODE_LEN.saturating_sub(self.depth()))
+            .flat_map(move |depth| (index << depth)..((index + 1) << depth))
+            .map(MorseSequence::from_index);
+        let long = morse_code
+            .replaced
+            .values()
+            .chain(&morse_code.custom)
+            .flat_map(CharacterSet::iter)
+            .map(|(_, code)| code)
+            .filter(|code| {
+                code.len() > MAX_CODE_LEN.max(sequence.len()) && code.starts_with(sequence)
+            })
+            .collect::<BTreeSet<_>>();
+        short
+            .chain(long)
+            .filter_map(move |code| morse_code.character(code))
+    }
+
+    fn child(self, sequence: MorseSequence) -> Option<Self> {
+        let node = Self { sequence, ..self };
+        (node.current().is_some() || node.reachable().next().is_some()).then_some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn navigates_tree() {
+        let morse_code = MorseCode::default();
+        let root = morse_code.tree().root();
+        assert_eq!(root.current(), None);
+        assert_eq!(root.depth(), 0);
+
+        let t = root.dash().unwrap();
+        assert_eq!(t.current(), Some('T'));
+        assert_eq!(t.reachable().take(2).collect::<String>(), "NM");
+
+        let zero = (0..5).try_fold(root, |node, _| node.dash()).unwrap();
+        assert_eq!(zero.current(), Some('0'));
+        assert_eq!(zero.depth(), 5);
+        assert_eq!(zero.sequence(), MorseSequence::from_code("-----"));
+        assert_eq!(zero.reachable().collect::<Vec<_>>(), []);
+        assert_eq!(zero.dot(), None);
+    }
+
+    #[test]
+    fn respects_priority() {
+        let morse_code = MorseCode::new(crate::Options {
+            priority: crate::MorseCharacterSet::Cyrillic,
+            ..Default::default()
+        });
+        let a = morse_code.tree().root().dot().and_then(MorseNode::dash);
+        assert_eq!(a.and_then(|node| node.current()), Some('А'));
+    }
+
+    #[test]
+    fn finds_near_misses() {
+        let mut set = CharacterSet::new();
+        for (c, code) in [
+            ('E', "."),
+            ('T', "-"),
+            ('A', ".-"),
+            ('I', ".."),
+            ('R', ".-."),
+        ] {
+            set.insert(c, MorseSequence::from_code(code)).unwrap();
+        }
+        let mut morse_code = MorseCode::new(crate::Options {
+            allowed: Some(alloc::borrow::Cow::Borrowed(&[])),
+            ..Default::default()
+        });
+        morse_code.add_character_set(set);
+        let tree = morse_code.tree();
+
+        // Changed, inserted and removed elements, with a symbol that matches nothing.
+        assert_eq!(tree.near_misses(".x"), ['A', 'E', 'I']);
+        // The empty code is never a candidate.
+        assert_eq!(tree.near_misses("-"), ['A', 'E']);
+        assert_eq!(tree.near_misses("...."), []);
+        assert_eq!(tree.near_misses(".-.-"), ['R']);
+    }
+}